@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Things that happen during play that achievements can be earned from.
+#[derive(Debug, Clone, Copy)]
+pub enum GameEvent {
+    RoundStarted { round: u32 },
+    RoundWon {
+        target: u32,
+        bubbles: usize,
+        /// None when the round had no clock.
+        seconds_left: Option<u64>,
+    },
+    Overshot,
+    TimedOut,
+}
+
+/// What has to happen to earn an achievement.
+#[derive(Debug, Clone, Copy)]
+pub enum Condition {
+    /// Win a round catching this many bubbles or fewer.
+    WinWithBubbles(usize),
+    /// Win a round against the clock with this many seconds or fewer left.
+    WinWithSecondsLeft(u64),
+    /// Win this many rounds in a row without overshooting.
+    CleanRounds(u32),
+    ReachRound(u32),
+    TargetAtLeast(u32),
+    /// Win this many rounds in total.
+    RoundsWon(u32),
+}
+
+pub struct Achievement {
+    /// Saved in the profile's achievements file, so mustn't change once released.
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+/// Every achievement, in the order they're shown in the gallery.
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_win",
+        name: "First pop",
+        description: "Win your first round",
+        condition: Condition::RoundsWon(1),
+    },
+    Achievement {
+        id: "one_bubble",
+        name: "Bullseye",
+        description: "Hit a target with a single bubble",
+        condition: Condition::WinWithBubbles(1),
+    },
+    Achievement {
+        id: "last_second",
+        name: "Photo finish",
+        description: "Win a round with 1 second left",
+        condition: Condition::WinWithSecondsLeft(1),
+    },
+    Achievement {
+        id: "clean_10",
+        name: "Steady hands",
+        description: "Win 10 rounds in a row without overshooting",
+        condition: Condition::CleanRounds(10),
+    },
+    Achievement {
+        id: "clean_25",
+        name: "Unflappable",
+        description: "Win 25 rounds in a row without overshooting",
+        condition: Condition::CleanRounds(25),
+    },
+    Achievement {
+        id: "round_10",
+        name: "Getting going",
+        description: "Reach round 10",
+        condition: Condition::ReachRound(10),
+    },
+    Achievement {
+        id: "round_20",
+        name: "Marathon",
+        description: "Reach round 20",
+        condition: Condition::ReachRound(20),
+    },
+    Achievement {
+        id: "big_target",
+        name: "Big numbers",
+        description: "Hit a target of 50 or more",
+        condition: Condition::TargetAtLeast(50),
+    },
+    Achievement {
+        id: "rounds_100",
+        name: "Century",
+        description: "Win 100 rounds",
+        condition: Condition::RoundsWon(100),
+    },
+];
+
+/// A profile's unlocked achievements and the counts needed to earn the rest. Saved between games.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    /// When each unlocked achievement was earned, in seconds since 1970-01-01, keyed by id.
+    pub unlocked: BTreeMap<String, u64>,
+    pub rounds_won: u32,
+    /// Rounds won since the last overshoot.
+    pub clean_rounds: u32,
+}
+
+impl Condition {
+    fn met_by(&self, event: &GameEvent, progress: &Achievements) -> bool {
+        match (*self, *event) {
+            (Condition::WinWithBubbles(most), GameEvent::RoundWon { bubbles, .. }) => bubbles <= most,
+            (Condition::WinWithSecondsLeft(most), GameEvent::RoundWon { seconds_left: Some(left), .. }) => {
+                left <= most
+            }
+            (Condition::CleanRounds(count), GameEvent::RoundWon { .. }) => progress.clean_rounds >= count,
+            (Condition::ReachRound(count), GameEvent::RoundStarted { round }) => round >= count,
+            (Condition::TargetAtLeast(least), GameEvent::RoundWon { target, .. }) => target >= least,
+            (Condition::RoundsWon(count), GameEvent::RoundWon { .. }) => progress.rounds_won >= count,
+            _ => false,
+        }
+    }
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.unlocked.contains_key(achievement.id)
+    }
+
+    /// Updates the counts for the event and returns any achievements it unlocked.
+    pub fn handle(&mut self, event: GameEvent, now_seconds: u64) -> Vec<&'static Achievement> {
+        match event {
+            GameEvent::RoundWon { .. } => {
+                self.rounds_won += 1;
+                self.clean_rounds += 1;
+            }
+            GameEvent::Overshot => self.clean_rounds = 0,
+            GameEvent::RoundStarted { .. } | GameEvent::TimedOut => (),
+        }
+
+        let earned: Vec<&'static Achievement> = ACHIEVEMENTS
+            .iter()
+            .filter(|a| !self.is_unlocked(a) && a.condition.met_by(&event, self))
+            .collect();
+        for achievement in &earned {
+            self.unlocked.insert(achievement.id.to_string(), now_seconds);
+        }
+        earned
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::levels::Level;
+
+pub const MAX_STARS: u8 = 3;
+
+/// Best star rating per level, keyed by level id. A level that's never been cleared has no entry.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    pub stars: BTreeMap<String, u8>,
+}
+
+impl CampaignProgress {
+    pub fn stars_for(&self, level: &Level) -> u8 {
+        self.stars.get(&level.id).copied().unwrap_or(0)
+    }
+
+    /// Keeps the better of the new and previous ratings. Returns true if it improved.
+    pub fn record(&mut self, level: &Level, stars: u8) -> bool {
+        if stars <= self.stars_for(level) {
+            return false;
+        }
+        self.stars.insert(level.id.clone(), stars);
+        true
+    }
+
+    /// The first level is always open, every other one needs the level before it cleared.
+    pub fn is_unlocked(&self, levels: &[Level], index: usize) -> bool {
+        index == 0 || self.stars_for(&levels[index - 1]) > 0
+    }
+
+    pub fn total_stars(&self) -> u32 {
+        self.stars.values().map(|s| *s as u32).sum()
+    }
+}
+
+/// One star for clearing the level, one for finishing with at least half the time left,
+/// and one for using no more catches than the level's par.
+pub fn rate(level: &Level, time_left_seconds: u64, catches: usize) -> u8 {
+    let mut stars = 1;
+    if time_left_seconds * 2 >= level.time_limit_seconds {
+        stars += 1;
+    }
+    if catches <= level.par_catches {
+        stars += 1;
+    }
+    stars
+}
@@ -4,22 +4,86 @@ use ggez::glam::Vec2;
 
 pub const MIN_TARGET: u32 = 5;
 pub const MAX_TARGET: u32 = 75;
+pub const RECENT_TARGETS_REMEMBERED: usize = 4;
+pub const MIN_COUNTDOWN_TARGET: u32 = 5;
+pub const MAX_COUNTDOWN_TARGET: u32 = 30;
 pub const STARTING_LIVES: u8 = 3;
+pub const MAX_LIVES: u8 = 6;
+pub const EXTRA_LIFE_EVERY_POINTS: u32 = 30;
+/// Beyond this many lives the HUD shows a count instead of a dot per life.
+pub const MAX_LIFE_DOTS: u8 = 10;
+pub const LIVES_HUD_WIDTH: f32 = 360.0;
 
 pub const MIN_BUBBLE_SPEED: f32 = 1.0;
 pub const MAX_BUBBLE_SPEED: f32 = 2.9;
 pub const SHIP_SPEED: Vec2 = Vec2::new(10.0, 8.0);
 
+// How often a bubble is picked to go over a number fact the player has been getting wrong:
+pub const FACT_REVIEW_PROBABILITY: f64 = 0.3;
+pub const NEGATIVE_BUBBLE_PROBABILITY: f64 = 0.2;
+// Once over the target, negatives turn up this often so there's a way back:
+pub const NEGATIVE_BUBBLE_WHEN_OVER_PROBABILITY: f64 = 0.6;
+pub const MAX_NEGATIVE_BUBBLE: i32 = 9;
+// Catches after going over the target that a negative bubble can still bring it back in:
+pub const NEGATIVE_RECOVERY_CATCHES: usize = 2;
 pub const RESPECT_SHORTFALL_PROBABILITY: f64 = 0.3;
 pub const NEW_BUBBLE_INTERVAL_FRAMES: usize = 30;
+// Any this many bubbles in a row can always finish the round between them:
+pub const SPAWN_PLAN_WINDOW: usize = 8;
 
 pub const STARTING_ROUND_TIME_SECONDS: u64 = 45;
 pub const MIN_ROUND_TIME_SECONDS: u64 = 15;
 pub const TIME_DEDUCTED_PER_ROUND: u64 = 5;
 
 pub const SECONDS_LEFT_PER_BONUS_POINT: u64 = 2;
+pub const FEW_BUBBLES_BONUS_BELOW: usize = 4;
+pub const STREAK_WINS_PER_MULTIPLIER: u32 = 3;
+pub const MAX_STREAK_MULTIPLIER: u32 = 4;
+pub const OVERSHOOT_PENALTY_POINTS: u32 = 1;
+
+pub const FEEDBACK_DISPLAY_SECONDS: u64 = 3;
+pub const ACHIEVEMENT_TOAST_SECONDS: u64 = 3;
+
+pub const OBJECTIVES_FROM_ROUND: u32 = 4;
+pub const VARIANT_OBJECTIVE_PROBABILITY: f64 = 0.35;
+
+pub const HAZARDS_FROM_ROUND: u32 = 3;
+pub const MINE_PROBABILITY: f64 = 0.08;
+pub const DECOY_PROBABILITY: f64 = 0.1;
+pub const HEART_BUBBLE_PROBABILITY: f64 = 0.01;
+pub const MINE_TIME_PENALTY_SECONDS: u64 = 5;
+pub const MINES_COST_LIFE_FROM_ROUND: u32 = 8;
+pub const WALLS_FROM_ROUND: u32 = 5;
+pub const WALLS_PER_ROUND: usize = 2;
+
+pub const RELEASE_COOLDOWN_SECONDS: u64 = 5;
+pub const OVERSHOOT_GRACE_SECONDS: u64 = 3;
+pub const HINTS_PER_ROUND: u32 = 2;
+pub const HINT_SCORE_COST: u32 = 1;
+pub const HINT_DISPLAY_SECONDS: u64 = 5;
+pub const RELEASE_TIME_PENALTY_SECONDS: u64 = 3;
+
+pub const LEVELS_DIR: &str = "levels";
+pub const SAVE_DIR: &str = "saves";
+pub const CAMPAIGN_SAVE_FILE: &str = "campaign.toml";
+pub const DAILY_SAVE_FILE: &str = "daily.toml";
+pub const CURRICULUM_SAVE_FILE: &str = "curriculum.toml";
+pub const FACTS_SAVE_FILE: &str = "facts.toml";
+pub const PROGRESS_REPORT_FILE: &str = "progress_report.txt";
+pub const SESSIONS_SAVE_FILE: &str = "sessions.toml";
+pub const SETTINGS_SAVE_FILE: &str = "settings.toml";
+pub const HIGH_SCORES_SAVE_FILE: &str = "high_scores.toml";
+pub const STATS_SAVE_FILE: &str = "stats.toml";
+pub const ACHIEVEMENTS_SAVE_FILE: &str = "achievements.toml";
+pub const PROFILES_SAVE_FILE: &str = "profiles.toml";
+pub const PROFILES_DIR: &str = "profiles";
 
 // Visual:
 pub const WINDOW_WIDTH: f32 = 1024.0;
 pub const WINDOW_HEIGHT: f32 = 768.0;
 pub const BUBBLE_RADIUS: f32 = 30.0;
+/// Tries at finding a spot for a new bubble that's clear of the others before giving up.
+pub const BUBBLE_PLACEMENT_TRIES: usize = 20;
+pub const SHIP_HALF_WIDTH: f32 = 20.0;
+pub const SHIP_HEIGHT: f32 = 30.0;
+pub const WALL_THICKNESS: f32 = 15.0;
@@ -0,0 +1,142 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// One step of the curriculum: which sums the bubbles show, and how big the numbers get.
+#[derive(Debug)]
+pub struct Stage {
+    pub name: &'static str,
+    pub operations: &'static [Operation],
+    /// The largest number written in any expression, the answer and a division's dividend aside.
+    pub max_operand: i32,
+    pub min_target: u32,
+    pub max_target: u32,
+}
+
+/// Stages in the order they're taught.
+pub const STAGES: &[Stage] = &[
+    Stage { name: "Adding within 10", operations: &[Operation::Add], max_operand: 5, min_target: 5, max_target: 10 },
+    Stage { name: "Adding and taking away within 20", operations: &[Operation::Add, Operation::Subtract], max_operand: 10, min_target: 10, max_target: 20 },
+    Stage { name: "Times tables to 5", operations: &[Operation::Multiply], max_operand: 5, min_target: 10, max_target: 30 },
+    Stage { name: "Times tables to 10", operations: &[Operation::Multiply], max_operand: 10, min_target: 20, max_target: 60 },
+    Stage { name: "Dividing", operations: &[Operation::Divide], max_operand: 10, min_target: 10, max_target: 30 },
+    Stage { name: "Everything", operations: &[Operation::Add, Operation::Subtract, Operation::Multiply, Operation::Divide], max_operand: 12, min_target: 20, max_target: 75 },
+];
+
+/// Clearing this many rounds in a row without a mistake moves the player on to the next stage.
+pub const ROUNDS_TO_MASTER: u32 = 3;
+
+/// How far the player has got through the curriculum. Saved between games.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CurriculumProgress {
+    pub stage: usize,
+    /// Rounds cleared in a row without a mistake at the current stage.
+    pub streak: u32,
+}
+
+impl CurriculumProgress {
+    pub fn stage(&self) -> &'static Stage {
+        &STAGES[self.stage.min(STAGES.len() - 1)]
+    }
+
+    /// Records a cleared round. Returns true if that moved the player on to a new stage.
+    pub fn round_cleared(&mut self, mistakes: u32) -> bool {
+        if mistakes > 0 {
+            self.streak = 0;
+            return false;
+        }
+        self.streak += 1;
+        if self.streak < ROUNDS_TO_MASTER || self.stage + 1 >= STAGES.len() {
+            return false;
+        }
+        self.stage += 1;
+        self.streak = 0;
+        true
+    }
+}
+
+/// Writes `value` as one of the stage's operations, e.g. 12 as "3×4" or "15−3".
+/// Falls back to an addition when the operation can't make the value within the stage's numbers,
+/// and to the plain number when an addition can't either.
+pub fn expression_for(value: i32, stage: &Stage, rng: &mut impl Rng) -> String {
+    let max = stage.max_operand;
+    let operation = stage.operations[rng.gen_range(0..stage.operations.len())];
+    match operation {
+        Operation::Multiply => {
+            let factors: Vec<i32> = (1..=max).filter(|f| value % f == 0 && value / f <= max).collect();
+            if !factors.is_empty() {
+                let left = factors[rng.gen_range(0..factors.len())];
+                return format!("{}×{}", left, value / left);
+            }
+        }
+        Operation::Divide => {
+            if value <= max {
+                let divisor = rng.gen_range(2..=max);
+                return format!("{}÷{}", value * divisor, divisor);
+            }
+        }
+        Operation::Subtract => {
+            // The number taken away from has to be within the stage too:
+            if value < max {
+                let taken = rng.gen_range(1..=max - value);
+                return format!("{}−{}", value + taken, taken);
+            }
+        }
+        Operation::Add => (),
+    }
+    if value < 2 || value > 2 * max {
+        return value.to_string();
+    }
+    let left = rng.gen_range((value - max).max(1)..=(value - 1).min(max));
+    format!("{}+{}", left, value - left)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn expressions_make_the_value_with_the_stages_numbers() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for stage in STAGES {
+            for value in 1..=stage.max_target as i32 {
+                for _ in 0..20 {
+                    let expression = expression_for(value, stage, &mut rng);
+                    let Some((at, operator)) = expression.char_indices().find(|(_, c)| "+−×÷".contains(*c)) else {
+                        assert_eq!(expression, value.to_string());
+                        continue;
+                    };
+                    let left: i32 = expression[..at].parse().unwrap();
+                    let right: i32 = expression[at + operator.len_utf8()..].parse().unwrap();
+                    let (result, operands) = match operator {
+                        '+' => (left + right, [left, right]),
+                        '−' => (left - right, [left, right]),
+                        '×' => (left * right, [left, right]),
+                        // The dividend is a times table answer, so it's the quotient that's bounded:
+                        _ => {
+                            assert_eq!(left % right, 0, "{}", expression);
+                            (left / right, [left / right, right])
+                        }
+                    };
+                    assert_eq!(result, value, "{}", expression);
+                    assert!(
+                        operands.iter().all(|n| (1..=stage.max_operand).contains(n)),
+                        "{}: {} goes past {}",
+                        stage.name,
+                        expression,
+                        stage.max_operand
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Today as a count of days since 1970-01-01 (UTC), so everyone gets the same challenge
+/// on the same day regardless of time zone.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / SECONDS_PER_DAY)
+        .unwrap_or(0)
+}
+
+/// Seed for one round of a day's challenge. Each round gets its own seed so the targets and
+/// bubbles of a round don't depend on how long the player took over the rounds before it.
+pub fn seed_for(day: u64, round: u32) -> u64 {
+    day.wrapping_mul(1_000_003).wrapping_add(round as u64)
+}
+
+/// Seed for the `index`th bubble spawned in a round of a day's challenge, so each bubble is
+/// the same for everyone however the round has gone so far.
+pub fn bubble_seed(day: u64, round: u32, index: u32) -> u64 {
+    seed_for(day, round) ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Formats a day number as YYYY-MM-DD.
+pub fn date_string(day: u64) -> String {
+    // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// Locally stored daily challenge history: one scored attempt per day, and the streak of
+/// consecutive days played.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DailyRecord {
+    pub last_played_day: Option<u64>,
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Score per day, keyed by day number.
+    pub scores: BTreeMap<String, u32>,
+}
+
+impl DailyRecord {
+    pub fn score_on(&self, day: u64) -> Option<u32> {
+        self.scores.get(&day.to_string()).copied()
+    }
+
+    pub fn has_played(&self, day: u64) -> bool {
+        self.score_on(day).is_some()
+    }
+
+    pub fn best_score(&self) -> Option<u32> {
+        self.scores.values().max().copied()
+    }
+
+    /// The streak only counts while it's still alive, i.e. the last game was today or yesterday.
+    pub fn current_streak(&self, today: u64) -> u32 {
+        match self.last_played_day {
+            Some(day) if day + 1 >= today => self.streak,
+            _ => 0,
+        }
+    }
+
+    /// Uses up the day's scored attempt. It's recorded as a score of 0 straight away so
+    /// quitting halfway through doesn't give you another go.
+    pub fn start_attempt(&mut self, day: u64) {
+        self.streak = match self.last_played_day {
+            Some(last) if last + 1 == day => self.streak + 1,
+            Some(last) if last == day => self.streak,
+            _ => 1,
+        };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_played_day = Some(day);
+        self.scores.insert(day.to_string(), 0);
+    }
+
+    pub fn finish_attempt(&mut self, day: u64, score: u32) {
+        self.scores.insert(day.to_string(), score);
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+/// The numbers each difficulty is played with.
+pub struct Tuning {
+    /// Round 1's targets are between these two.
+    pub min_target: u32,
+    pub first_round_max_target: u32,
+    /// How much further both ends of the target range go each round.
+    pub target_growth_per_round: u32,
+    pub max_target: u32,
+    /// Favour targets that take more bubbles and more working out.
+    pub favour_interesting_targets: bool,
+    /// Speed the ship gains per frame while a direction is held, unless it's on arcade movement.
+    pub ship_acceleration: f32,
+    /// How much of its speed the ship keeps from one frame to the next.
+    pub ship_drag: f32,
+    pub ship_max_speed: f32,
+}
+
+const EASY: Tuning = Tuning {
+    min_target: 5,
+    first_round_max_target: 12,
+    target_growth_per_round: 2,
+    max_target: 40,
+    favour_interesting_targets: false,
+    ship_acceleration: 2.5,
+    ship_drag: 0.75,
+    ship_max_speed: 9.0,
+};
+
+const NORMAL: Tuning = Tuning {
+    min_target: constants::MIN_TARGET,
+    first_round_max_target: 20,
+    target_growth_per_round: 5,
+    max_target: constants::MAX_TARGET,
+    favour_interesting_targets: true,
+    ship_acceleration: 2.0,
+    ship_drag: 0.85,
+    ship_max_speed: 10.0,
+};
+
+const HARD: Tuning = Tuning {
+    min_target: 10,
+    first_round_max_target: 30,
+    target_growth_per_round: 8,
+    max_target: 120,
+    favour_interesting_targets: true,
+    ship_acceleration: 1.2,
+    ship_drag: 0.9,
+    ship_max_speed: 11.0,
+};
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    pub fn tuning(&self) -> &'static Tuning {
+        match self {
+            Difficulty::Easy => &EASY,
+            Difficulty::Normal => &NORMAL,
+            Difficulty::Hard => &HARD,
+        }
+    }
+}
+
+impl Tuning {
+    /// The targets a round can have. The top end grows every round, and the bottom end
+    /// at half the pace, so later rounds stop asking for the smallest targets.
+    pub fn target_range(&self, round: u32) -> (u32, u32) {
+        let grown = self.target_growth_per_round * round.saturating_sub(1);
+        let max = (self.first_round_max_target + grown).min(self.max_target);
+        let min = (self.min_target + grown / 2).min(self.max_target / 2);
+        (min, max)
+    }
+}
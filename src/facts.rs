@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Facts move up a Leitner box each time they're got right, and back to box 0 when they're
+/// got wrong. A fact in box n comes up for review 2^n rounds after it was last seen.
+const MAX_BOX: u8 = 5;
+
+/// One number fact, e.g. 7 + 8, and how the player has done with it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactRecord {
+    pub left: i32,
+    pub right: i32,
+    pub subtract: bool,
+    pub correct: u32,
+    pub wrong: u32,
+    pub review_box: u8,
+    pub last_seen_round: u64,
+}
+
+/// Every number fact the player has worked through while catching bubbles: the running
+/// total plus (or, counting down, minus) the number just caught. Catches that overshoot
+/// count as getting the fact wrong. Saved between games.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FactBook {
+    pub rounds_played: u64,
+    /// Keyed by the fact as written, e.g. "7 + 8". Additions are stored smaller number first.
+    pub facts: BTreeMap<String, FactRecord>,
+}
+
+/// Writes a fact the way it's stored and shown, turning adding a negative into a subtraction.
+fn normalise(left: i32, right: i32, subtract: bool) -> (i32, i32, bool) {
+    match (subtract, right < 0) {
+        (false, false) => (left.min(right), left.max(right), false),
+        (false, true) => (left, -right, true),
+        (true, false) => (left, right, true),
+        (true, true) => (left.min(-right), left.max(-right), false),
+    }
+}
+
+fn fact_name(left: i32, right: i32, subtract: bool) -> String {
+    format!("{} {} {}", left, if subtract { "-" } else { "+" }, right)
+}
+
+impl FactRecord {
+    pub fn name(&self) -> String {
+        fact_name(self.left, self.right, self.subtract)
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        self.correct as f32 / (self.correct + self.wrong).max(1) as f32
+    }
+
+    /// The round from which the fact is due to come up again.
+    pub fn due_round(&self) -> u64 {
+        self.last_seen_round + (1 << self.review_box)
+    }
+}
+
+impl FactBook {
+    pub fn next_round(&mut self) {
+        self.rounds_played += 1;
+    }
+
+    pub fn record(&mut self, left: i32, right: i32, subtract: bool, correct: bool) {
+        let (left, right, subtract) = normalise(left, right, subtract);
+        let rounds_played = self.rounds_played;
+        let fact = self.facts.entry(fact_name(left, right, subtract)).or_insert(FactRecord {
+            left,
+            right,
+            subtract,
+            ..FactRecord::default()
+        });
+        if correct {
+            fact.correct += 1;
+            fact.review_box = (fact.review_box + 1).min(MAX_BOX);
+        } else {
+            fact.wrong += 1;
+            fact.review_box = 0;
+        }
+        fact.last_seen_round = rounds_played;
+    }
+
+    /// A number to spawn that sets up a due fact from the current total, e.g. an 8 when the
+    /// total is 7 and 7 + 8 was got wrong recently. The weakest fact due for review wins.
+    pub fn review_number(&self, total: i32, subtract: bool) -> Option<i32> {
+        self.facts
+            .values()
+            .filter(|f| f.subtract == subtract && f.wrong > 0 && f.due_round() <= self.rounds_played)
+            .filter_map(|f| {
+                if f.left == total {
+                    Some((f, f.right))
+                } else if !subtract && f.right == total {
+                    Some((f, f.left))
+                } else {
+                    None
+                }
+            })
+            .min_by(|(a, _), (b, _)| a.accuracy().total_cmp(&b.accuracy()))
+            .map(|(_, number)| number)
+    }
+
+    /// A plain text progress report, weakest facts first.
+    pub fn report(&self) -> String {
+        let mut facts: Vec<&FactRecord> = self.facts.values().collect();
+        facts.sort_by(|a, b| a.accuracy().total_cmp(&b.accuracy()).then(b.wrong.cmp(&a.wrong)));
+
+        let mut report = format!("Number facts after {} rounds, weakest first\n\n", self.rounds_played);
+        report += &format!("{:<12}{:>8}{:>8}{:>10}  {}\n", "Fact", "Right", "Wrong", "Accuracy", "Next review");
+        for fact in facts {
+            let review = match fact.due_round().saturating_sub(self.rounds_played) {
+                0 => "due now".to_string(),
+                1 => "next round".to_string(),
+                rounds => format!("in {} rounds", rounds),
+            };
+            report += &format!(
+                "{:<12}{:>8}{:>8}{:>9.0}%  {}\n",
+                fact.name(),
+                fact.correct,
+                fact.wrong,
+                fact.accuracy() * 100.0,
+                review
+            );
+        }
+        report
+    }
+}
@@ -1,53 +1,315 @@
-use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
 
 use ggez::event::EventHandler;
 use ggez::glam::Vec2;
 use ggez::input::keyboard::KeyCode;
 use ggez::input::keyboard::KeyInput;
 use ggez::{Context, GameResult};
-use rand::{self, prelude::*};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 
+use crate::achievements::GameEvent;
+use crate::campaign;
 use crate::constants;
-use crate::constants::STARTING_LIVES;
+use crate::daily;
+use crate::difficulty::Difficulty;
+use crate::levels::{self, Level};
+use crate::movement;
+use crate::objectives::{Objective, Progress};
+use crate::persistence;
+use crate::profiles::{self, ProfileIndex};
 use crate::renderer;
-use crate::structs::{Bubble, GameMode, GameState, Ship};
+use crate::policies::{RoundSetup, WinInfo};
+use crate::rules::{self, Clock, OvershootRule, Rules};
+use crate::scoring::ScoreBreakdown;
+use crate::spawner::{self, DailySpawner, ShortfallSpawner, SpawnContext};
+use crate::session::{self, RoundLog, RoundOutcome};
+use crate::settings;
+use crate::structs::{Bubble, BubbleKind, GameMode, GameState, MinePenalty, PlayMode, ProfileEdit, Ship, Wall};
 
 impl GameState {
     pub fn new(ctx: &Context) -> Self {
-        Self {
+        let mut game = Self {
             window_size: ctx.gfx.window().inner_size(),
             lives_remaining: constants::STARTING_LIVES,
             current_mode: GameMode::IntroScreen,
+            play_mode: PlayMode::Endless,
+            rules: Rules::standard(),
+            rng: ChaCha8Rng::from_entropy(),
             current_round: 0,
+            profiles: ProfileIndex::load(),
+            settings: Default::default(),
+            high_scores: Default::default(),
+            new_best_score: false,
+            stats: Default::default(),
+            achievements: Default::default(),
+            achievement_toasts: Default::default(),
+            toast_until_seconds: None,
+            profile_cursor: 0,
+            profile_edit: ProfileEdit::Browsing,
+            profile_error: None,
+            binding_cursor: 0,
+            awaiting_binding: false,
+            level: Level::default(),
+            campaign_levels: levels::load_campaign(Path::new(constants::LEVELS_DIR)),
+            campaign_progress: Default::default(),
+            level_select_cursor: 0,
+            last_round_stars: 0,
+            daily_record: Default::default(),
+            daily_day: daily::today(),
+            curriculum_progress: Default::default(),
+            mistakes_this_round: 0,
+            fact_book: Default::default(),
+            intro_message: None,
+            session_log: Default::default(),
+            session_started_at: session::now(),
+            round_log_start_seconds: 0,
+            feedback_message: None,
+            overshoot_reason: String::new(),
+            feedback_until_seconds: 0,
+            spawner: Box::new(ShortfallSpawner::new(0)),
             round_allowed_time_seconds: constants::STARTING_ROUND_TIME_SECONDS,
             round_start_time_seconds: ctx.time.time_since_start().as_secs(),
             round_time_remaining_seconds: constants::STARTING_ROUND_TIME_SECONDS,
+            round_time_penalty_seconds: 0,
             bubbles: vec![],
+            walls: vec![],
             next_bubble_index: 0,
+            round_spawns: 0,
             current_target: 0,
+            recent_targets: Default::default(),
             numbers_caught: vec![],
+            caught_kinds: vec![],
+            last_release_time_seconds: None,
+            overshoot_grace_until_seconds: None,
+            hints_used: 0,
+            hint_until_seconds: None,
             score: 0,
-            round_time_bonus: 0,
+            targets_hit: 0,
+            score_breakdown: Default::default(),
+            win_streak: 0,
+            next_extra_life_score: 0,
+            extra_life_earned: false,
             ship: Ship {
                 position: Vec2::new(500.0, 500.0),
                 speed: constants::SHIP_SPEED,
+                velocity: Vec2::ZERO,
             },
             paused: false,
+        };
+        game.load_profile();
+        game
+    }
+
+    /// Where the current player's copy of a save file lives.
+    pub fn profile_path(&self, file_name: &str) -> PathBuf {
+        profiles::profile_path(&self.profiles.current, file_name)
+    }
+
+    /// Reads everything that belongs to the current player from their profile.
+    fn load_profile(&mut self) {
+        self.campaign_progress = persistence::load_or_default(&self.profile_path(constants::CAMPAIGN_SAVE_FILE));
+        self.daily_record = persistence::load_or_default(&self.profile_path(constants::DAILY_SAVE_FILE));
+        self.curriculum_progress = persistence::load_or_default(&self.profile_path(constants::CURRICULUM_SAVE_FILE));
+        self.fact_book = persistence::load_or_default(&self.profile_path(constants::FACTS_SAVE_FILE));
+        self.session_log = persistence::load_or_default(&self.profile_path(constants::SESSIONS_SAVE_FILE));
+        self.settings = persistence::load_or_default(&self.profile_path(constants::SETTINGS_SAVE_FILE));
+        self.high_scores = persistence::load_or_default(&self.profile_path(constants::HIGH_SCORES_SAVE_FILE));
+        self.stats = persistence::load_or_default(&self.profile_path(constants::STATS_SAVE_FILE));
+        self.achievements = persistence::load_or_default(&self.profile_path(constants::ACHIEVEMENTS_SAVE_FILE));
+        self.achievement_toasts.clear();
+        self.toast_until_seconds = None;
+        self.settings.zen_target_range = self.settings.zen_target_range.min(rules::ZEN_TARGET_RANGES.len() - 1);
+        self.settings.zen_speed = self.settings.zen_speed.min(rules::ZEN_SPEEDS.len() - 1);
+        self.level_select_cursor = 0;
+    }
+
+    fn save_stats(&self) {
+        persistence::save(&self.profile_path(constants::STATS_SAVE_FILE), &self.stats);
+    }
+
+    /// Passes the event on to the achievements, queueing a toast for each one it unlocks.
+    fn achievement_event(&mut self, event: GameEvent) {
+        for achievement in self.achievements.handle(event, session::now()) {
+            self.achievement_toasts.push_back(achievement.name.to_string());
+        }
+        persistence::save(&self.profile_path(constants::ACHIEVEMENTS_SAVE_FILE), &self.achievements);
+    }
+
+    /// Shows each queued toast for a few seconds, then moves on to the next.
+    fn update_toasts(&mut self, now_seconds: u64) {
+        match self.toast_until_seconds {
+            None if !self.achievement_toasts.is_empty() => {
+                self.toast_until_seconds = Some(now_seconds + constants::ACHIEVEMENT_TOAST_SECONDS);
+            }
+            Some(until) if now_seconds >= until => {
+                self.achievement_toasts.pop_front();
+                self.toast_until_seconds = None;
+            }
+            _ => (),
+        }
+    }
+
+    fn save_settings(&self) {
+        persistence::save(&self.profile_path(constants::SETTINGS_SAVE_FILE), &self.settings);
+    }
+
+    fn open_profiles(&mut self) {
+        self.profile_cursor = self.profiles.names.iter().position(|n| *n == self.profiles.current).unwrap_or(0);
+        self.profile_edit = ProfileEdit::Browsing;
+        self.profile_error = None;
+        self.current_mode = GameMode::ProfilesScreen;
+    }
+
+    /// The profiles screen lists every profile, then a "new profile" entry at the end.
+    fn profiles_key(&mut self, keycode: KeyCode) {
+        let selected = self.profiles.names.get(self.profile_cursor).cloned();
+        match (&mut self.profile_edit, keycode) {
+            (ProfileEdit::Naming { text, .. }, KeyCode::Back) => {
+                text.pop();
+            }
+            (ProfileEdit::Naming { renaming, text }, KeyCode::Return) => {
+                let result = match renaming {
+                    Some(old_name) => self.profiles.rename(old_name, text),
+                    None => self.profiles.create(text),
+                };
+                match result {
+                    Ok(name) => {
+                        self.profile_cursor = self.profiles.names.iter().position(|n| *n == name).unwrap_or(0);
+                        self.profile_edit = ProfileEdit::Browsing;
+                        self.profile_error = None;
+                    }
+                    Err(e) => self.profile_error = Some(e),
+                }
+            }
+            (ProfileEdit::Naming { .. }, KeyCode::Escape) => {
+                self.profile_edit = ProfileEdit::Browsing;
+                self.profile_error = None;
+            }
+            (ProfileEdit::Naming { .. }, _) => (),
+            (ProfileEdit::ConfirmingDelete, KeyCode::Y) => {
+                if let Some(name) = selected {
+                    let was_current = name == self.profiles.current;
+                    match self.profiles.delete(&name) {
+                        Ok(()) => {
+                            self.profile_cursor = self.profile_cursor.min(self.profiles.names.len() - 1);
+                            if was_current {
+                                self.load_profile();
+                            }
+                        }
+                        Err(e) => self.profile_error = Some(e),
+                    }
+                }
+                self.profile_edit = ProfileEdit::Browsing;
+            }
+            (ProfileEdit::ConfirmingDelete, _) => self.profile_edit = ProfileEdit::Browsing,
+            (ProfileEdit::Browsing, KeyCode::Up) => self.profile_cursor = self.profile_cursor.saturating_sub(1),
+            (ProfileEdit::Browsing, KeyCode::Down) => {
+                self.profile_cursor = (self.profile_cursor + 1).min(self.profiles.names.len())
+            }
+            (ProfileEdit::Browsing, KeyCode::Return | KeyCode::Space) => match selected {
+                Some(name) => {
+                    self.profiles.current = name;
+                    self.profiles.save();
+                    self.load_profile();
+                    self.current_mode = GameMode::IntroScreen;
+                }
+                None => {
+                    self.profile_error = None;
+                    self.profile_edit = ProfileEdit::Naming { renaming: None, text: String::new() };
+                }
+            },
+            (ProfileEdit::Browsing, KeyCode::F2) => {
+                if let Some(name) = selected {
+                    self.profile_error = None;
+                    self.profile_edit = ProfileEdit::Naming { renaming: Some(name.clone()), text: name };
+                }
+            }
+            (ProfileEdit::Browsing, KeyCode::Delete | KeyCode::X) => {
+                if selected.is_some() {
+                    self.profile_error = None;
+                    self.profile_edit = ProfileEdit::ConfirmingDelete;
+                }
+            }
+            (ProfileEdit::Browsing, KeyCode::K) => {
+                self.binding_cursor = 0;
+                self.awaiting_binding = false;
+                self.current_mode = GameMode::KeyBindingsScreen;
+            }
+            (ProfileEdit::Browsing, KeyCode::Escape) => self.current_mode = GameMode::IntroScreen,
+            (ProfileEdit::Browsing, _) => (),
+        }
+    }
+
+    /// Key bindings for the current profile: pick an action, press return, then press its new key.
+    fn key_bindings_key(&mut self, keycode: KeyCode) {
+        if self.awaiting_binding {
+            self.awaiting_binding = false;
+            if settings::is_bindable(keycode) {
+                self.settings.keys.bind(settings::ACTIONS[self.binding_cursor], keycode);
+                self.save_settings();
+            }
+            return;
+        }
+        match keycode {
+            KeyCode::Up => self.binding_cursor = self.binding_cursor.saturating_sub(1),
+            KeyCode::Down => self.binding_cursor = (self.binding_cursor + 1).min(settings::ACTIONS.len() - 1),
+            KeyCode::Return => self.awaiting_binding = true,
+            KeyCode::Escape => self.open_profiles(),
+            _ => (),
+        }
+    }
+
+    /// Typed characters go into the name being edited on the profiles screen. Names can't
+    /// start with a space, which also drops the space that opened the name entry.
+    pub fn type_character(&mut self, character: char) {
+        if let (GameMode::ProfilesScreen, ProfileEdit::Naming { text, .. }) = (&self.current_mode, &mut self.profile_edit) {
+            if text.is_empty() && character.is_whitespace() {
+                return;
+            }
+            if !character.is_control() && text.chars().count() < profiles::MAX_NAME_LENGTH {
+                text.push(character);
+            }
+        }
+    }
+
+    /// Keeps the player's best score for the mode they've just finished.
+    fn record_high_score(&mut self) {
+        let score = self.final_score();
+        self.new_best_score = self.high_scores.record(session::mode_name(self.play_mode), score) && score > 0;
+        if self.new_best_score {
+            persistence::save(&self.profile_path(constants::HIGH_SCORES_SAVE_FILE), &self.high_scores);
         }
     }
 
-    pub fn random_between(lower: f32, upper: f32) -> f32 {
-        let mut rng: ThreadRng = rand::thread_rng();
-        let window: f32 = upper - lower;
-        return lower + ((rng.gen::<f64>()) * window as f64) as f32;
+    /// What a finished game is ranked by: the targets hit in time attack, the score otherwise.
+    pub fn final_score(&self) -> u32 {
+        match self.play_mode {
+            PlayMode::TimeAttack => self.targets_hit,
+            _ => self.score,
+        }
     }
 
-    pub fn random_target() -> u32 {
-        return Self::random_between(constants::MIN_TARGET as f32, constants::MAX_TARGET as f32)
-            as u32;
+    pub fn random_between(&mut self, lower: f32, upper: f32) -> f32 {
+        spawner::random_between(&mut self.rng, lower, upper)
+    }
+
+    /// The daily challenge is always played on Normal, so it's the same for everyone.
+    pub fn difficulty(&self) -> Difficulty {
+        match self.play_mode {
+            PlayMode::Daily { .. } => Difficulty::Normal,
+            _ => self.settings.difficulty,
+        }
     }
 
-    pub fn overlaps_with_bubble(bubbles: &Vec<Bubble>, pos: Vec2) -> Option<&Bubble> {
+    pub fn campaign_level_index(&self) -> Option<usize> {
+        match self.play_mode {
+            PlayMode::Campaign { level_index } => Some(level_index),
+            _ => None,
+        }
+    }
+
+    pub fn overlaps_with_bubble(bubbles: &[Bubble], pos: Vec2) -> Option<&Bubble> {
         for bubble in bubbles.iter() {
             let overlaps_x: bool = pos.x >= bubble.position.x - constants::BUBBLE_RADIUS * 2.0
                 && pos.x <= bubble.position.x + constants::BUBBLE_RADIUS * 2.0;
@@ -57,141 +319,702 @@ impl GameState {
                 return Some(bubble);
             }
         }
-        return None;
+        None
     }
 
-    pub fn add_bubble(&mut self) {
-        /*
-         * To balance making the game challenging but not too hard,
-         * 50% of the time we make sure the bubble is in the same range as the current shortfall,
-         * so you're not stuck needing a 2 and having to wait through random numbers between 1 and 100 for e.g.
-         */
-
-        let mut rng = rand::thread_rng();
-        let should_respect_shortfall_range: bool =
-            rng.gen::<f64>() < constants::RESPECT_SHORTFALL_PROBABILITY;
-
-        let mut upper_limit: u32 = self.current_target;
-        if should_respect_shortfall_range {
-            let shortfall = self.current_target - self.compute_caught_sum();
-            upper_limit = shortfall;
-        }
-        let bubble_number = Self::random_between(1.0, upper_limit as f32) as u32;
-
-        let mut bubble_x: f32 = Self::random_between(1.0, (self.window_size.width - 10) as f32);
-        while Option::is_some(&Self::overlaps_with_bubble(
-            &self.bubbles,
-            Vec2::new(bubble_x as f32, 0.0),
-        )) {
-            bubble_x = Self::random_between(10.0, (self.window_size.width - 10) as f32);
-        }
-        let bubble_y = 0;
+    /// Picks an x position along the top edge that doesn't overlap an existing bubble,
+    /// or None if the top edge is too crowded to find one.
+    fn free_bubble_x(bubbles: &[Bubble], width: f32, rng: &mut ChaCha8Rng) -> Option<f32> {
+        for _ in 0..constants::BUBBLE_PLACEMENT_TRIES {
+            let bubble_x: f32 = spawner::random_between(rng, 10.0, width - 10.0);
+            if Self::overlaps_with_bubble(bubbles, Vec2::new(bubble_x, 0.0)).is_none() {
+                return Some(bubble_x);
+            }
+        }
+        None
+    }
+
+    fn random_bubble_speed(&mut self) -> f32 {
+        self.random_between(self.level.min_speed, self.level.max_speed)
+    }
+
+    fn push_bubble(&mut self, kind: BubbleKind, number: i32, bubble_x: f32) {
+        let pattern = movement::choose_pattern(self.level.pattern_table(), self.current_round, &mut self.rng);
+        let base_speed = self.random_bubble_speed();
+        let label = match kind {
+            BubbleKind::Number => self.spawner.label(number, &mut self.rng),
+            _ => None,
+        };
         let bubble: Bubble = Bubble {
             index: self.next_bubble_index,
-            number: bubble_number,
-            position: Vec2::new(bubble_x as f32, bubble_y as f32),
-            speed: Vec2::new(
-                0.0,
-                Self::random_between(
-                    constants::MIN_BUBBLE_SPEED + (self.current_round as f32 / 2.0),
-                    constants::MAX_BUBBLE_SPEED + (self.current_round as f32 / 2.0),
-                ),
-            ),
+            kind,
+            number,
+            label,
+            position: Vec2::new(bubble_x, pattern.start_y(self.window_size.height as f32)),
+            speed: pattern.initial_speed(base_speed, &mut self.rng),
+            pattern,
+            age_frames: 0,
+            anchor_x: bubble_x,
         };
         self.bubbles.push(bubble);
         self.next_bubble_index += 1;
     }
 
+    pub fn add_bubble(&mut self) {
+        if let PlayMode::Daily { .. } = self.play_mode {
+            self.add_daily_bubble();
+            return;
+        }
+        // With the top edge crowded, this spawn is skipped before the spawner picks anything:
+        let Some(bubble_x) = Self::free_bubble_x(&self.bubbles, self.window_size.width as f32, &mut self.rng) else {
+            return;
+        };
+        let review = self.review_number();
+        let hearts = self.rules.lives.counts_lives() && self.lives_remaining < self.rules.lives.max_lives();
+        let (kind, number) = spawner::next_bubble(
+            self.spawner.as_mut(),
+            &mut SpawnContext {
+                level: &self.level,
+                target: self.current_target,
+                caught: &self.numbers_caught,
+                rng: &mut self.rng,
+                review,
+            },
+            hearts,
+        );
+        self.push_bubble(kind, number, bubble_x);
+    }
+
+    /// The daily challenge has to send everyone the same bubbles, whatever they catch. Each one
+    /// draws from its own seed, hearts turn up whatever the lives, and where it goes comes from
+    /// a separate stream, so a crowded top edge neither skips it nor changes what comes next.
+    fn add_daily_bubble(&mut self) {
+        let mut bubble_rng = ChaCha8Rng::seed_from_u64(daily::bubble_seed(self.daily_day, self.current_round, self.round_spawns));
+        self.round_spawns += 1;
+        let mut place_rng = ChaCha8Rng::seed_from_u64(bubble_rng.gen());
+        let width = self.window_size.width as f32;
+        let bubble_x = Self::free_bubble_x(&self.bubbles, width, &mut place_rng).unwrap_or(width / 2.0);
+        self.rng = bubble_rng;
+        let (kind, number) = spawner::next_bubble(
+            self.spawner.as_mut(),
+            &mut SpawnContext {
+                level: &self.level,
+                target: self.current_target,
+                caught: &self.numbers_caught,
+                rng: &mut self.rng,
+                review: None,
+            },
+            self.rules.lives.counts_lives(),
+        );
+        self.push_bubble(kind, number, bubble_x);
+    }
+
+    /// The running total for number facts: what's been added so far, or counting down, what's left.
+    fn fact_total(&self) -> Option<(i32, bool)> {
+        match self.level.objective {
+            Objective::Sum => Some((self.compute_caught_sum(), false)),
+            Objective::Countdown => Some((self.current_target as i32 - self.compute_caught_sum(), true)),
+            _ => None,
+        }
+    }
+
+    /// A number that brings back a number fact due for review, for the spawner to sometimes
+    /// use. The daily challenge is left alone so it stays the same for everyone.
+    fn review_number(&self) -> Option<i32> {
+        if matches!(self.play_mode, PlayMode::Daily { .. }) {
+            return None;
+        }
+        let (total, subtract) = self.fact_total()?;
+        self.fact_book.review_number(total, subtract)
+    }
+
     fn update_bubbles(&mut self) {
+        let window_width = self.window_size.width as f32;
+        let window_height = self.window_size.height as f32;
         for bubble in self.bubbles.iter_mut() {
-            bubble.position.y += bubble.speed.y;
-            bubble.position.x += bubble.speed.x;
+            let pattern = bubble.pattern;
+            pattern.step(bubble, window_width);
         }
+        // Drop bubbles that have left the field, whichever way they were heading:
+        self.bubbles.retain(|b| {
+            b.position.y > -constants::BUBBLE_RADIUS * 2.0
+                && b.position.y < window_height + constants::BUBBLE_RADIUS * 2.0
+        });
     }
 
-    fn check_bubble_caught(&mut self) {
-        match Self::overlaps_with_bubble(&self.bubbles, self.ship.position) {
-            Some(bubble) => {
-                self.numbers_caught.push(bubble.number);
-                let index_to_remove = self
-                    .bubbles
-                    .iter()
-                    .position(|b| (*b).index == bubble.index)
-                    .unwrap();
-                self.bubbles.remove(index_to_remove);
+    fn check_bubble_caught(&mut self, now_seconds: u64) {
+        if let Some(bubble) = Self::overlaps_with_bubble(&self.bubbles, self.ship.position) {
+            let kind = bubble.kind;
+            let fact_total = self.fact_total();
+            match kind {
+                BubbleKind::Number | BubbleKind::Decoy => {
+                    self.numbers_caught.push(bubble.number);
+                    self.caught_kinds.push(kind);
+                }
+                BubbleKind::Mine | BubbleKind::Heart => (),
+            }
+            if kind == BubbleKind::Number {
+                self.stats.caught(bubble.number);
+            }
+            // The first catch of a sum isn't a fact yet, there's nothing to add it to:
+            if let (BubbleKind::Number, Some((total, subtract))) = (kind, fact_total) {
+                if subtract || self.numbers_caught.len() > 1 {
+                    let progress = self.level.objective.evaluate(&self.numbers_caught, self.current_target);
+                    let correct = !matches!(progress, Progress::Failed(_));
+                    self.fact_book.record(total, bubble.number, subtract, correct);
+                }
+            }
+            let index_to_remove = self
+                .bubbles
+                .iter()
+                .position(|b| b.index == bubble.index)
+                .unwrap();
+            self.bubbles.remove(index_to_remove);
+
+            if kind == BubbleKind::Mine {
+                self.hit_mine(now_seconds);
+            }
+            if kind == BubbleKind::Heart && self.gain_life() {
+                self.extra_life_earned = true;
+                self.show_feedback("Extra life!".to_string(), now_seconds);
             }
-            None => (),
         }
     }
 
-    pub fn compute_caught_sum(&self) -> u32 {
-        let mut total: u32 = 0;
+    fn hit_mine(&mut self, now_seconds: u64) {
+        match self.rules.lives.mine_penalty(self.level.mine_penalty) {
+            MinePenalty::Life => {
+                self.deduct_life();
+                let out = self.rules.lives.is_out(self.lives_remaining);
+                self.stats.hit_mine(out);
+                if out {
+                    // The last life ends the round here, so it's logged like any other lost round:
+                    self.win_streak = 0;
+                    self.log_round(RoundOutcome::HitMine, now_seconds);
+                    self.game_over();
+                }
+            }
+            MinePenalty::Seconds(seconds) => {
+                self.stats.hit_mine(false);
+                self.round_time_penalty_seconds += seconds;
+            }
+        }
+    }
+
+    /// How far the ship moves this frame with the held `direction`. Arcade movement is full
+    /// speed on each held axis straight away. Otherwise the ship speeds up towards the held
+    /// direction and drifts to a stop, and going diagonally is no faster than going straight.
+    fn ship_movement(&mut self, direction: Vec2) -> Vec2 {
+        if self.settings.arcade_movement {
+            return direction * self.ship.speed;
+        }
+        let tuning = self.difficulty().tuning();
+        let velocity =
+            self.ship.velocity * tuning.ship_drag + direction.normalize_or_zero() * tuning.ship_acceleration;
+        self.ship.velocity = velocity.clamp_length_max(tuning.ship_max_speed);
+        self.ship.velocity
+    }
+
+    /// The furthest the ship can go towards each edge of the window.
+    fn ship_in_bounds(&self, pos: Vec2) -> bool {
+        pos.x >= -5.0
+            && pos.x <= (self.window_size.width - 5) as f32
+            && pos.y >= -8.0
+            && pos.y <= (self.window_size.height - 17) as f32
+    }
+
+    /// Whether the ship, with its tip at the given position, would overlap any wall.
+    fn ship_blocked_at(&self, pos: Vec2) -> bool {
+        self.walls.iter().any(|wall| {
+            pos.x + constants::SHIP_HALF_WIDTH >= wall.position.x
+                && pos.x - constants::SHIP_HALF_WIDTH <= wall.position.x + wall.size.x
+                && pos.y + constants::SHIP_HEIGHT >= wall.position.y
+                && pos.y <= wall.position.y + wall.size.y
+        })
+    }
+
+    fn place_walls(&mut self) {
+        self.walls = vec![];
+        let width = self.window_size.width as f32;
+        let height = self.window_size.height as f32;
+        while self.walls.len() < self.level.walls {
+            let wall = Wall {
+                position: Vec2::new(
+                    self.random_between(0.0, width - 250.0),
+                    self.random_between(250.0, height - 150.0),
+                ),
+                size: Vec2::new(self.random_between(120.0, 250.0), constants::WALL_THICKNESS),
+            };
+            self.walls.push(wall);
+            // Never trap the ship inside a freshly placed wall:
+            if self.ship_blocked_at(self.ship.position) {
+                self.walls.pop();
+            }
+        }
+    }
+
+    pub fn compute_caught_sum(&self) -> i32 {
+        let mut total: i32 = 0;
         for i in self.numbers_caught.iter() {
             total += i;
         }
-        return total;
+        total
+    }
+
+    /// Seconds until the last caught number can be released again, 0 if it's ready now.
+    pub fn release_cooldown_remaining(&self, now_seconds: u64) -> u64 {
+        match self.last_release_time_seconds {
+            Some(released_at) => {
+                (released_at + constants::RELEASE_COOLDOWN_SECONDS).saturating_sub(now_seconds)
+            }
+            None => 0,
+        }
+    }
+
+    /// Ejects the most recently caught number back into the field as a fresh bubble.
+    /// Costs some of the round's time and can only be used once per cooldown period.
+    fn release_last_caught(&mut self, now_seconds: u64) {
+        if !self.level.release_allowed || self.release_cooldown_remaining(now_seconds) > 0 {
+            return;
+        }
+        if self.numbers_caught.is_empty() {
+            return;
+        }
+        let Some(bubble_x) = Self::free_bubble_x(&self.bubbles, self.window_size.width as f32, &mut self.rng) else {
+            self.show_feedback("No room to release it".to_string(), now_seconds);
+            return;
+        };
+        if let Some((kind, number)) = self.take_back_last_catch() {
+            self.push_bubble(kind, number, bubble_x);
+            self.round_time_penalty_seconds += constants::RELEASE_TIME_PENALTY_SECONDS;
+            self.last_release_time_seconds = Some(now_seconds);
+            self.overshoot_grace_until_seconds = None;
+        }
+    }
+
+    fn take_back_last_catch(&mut self) -> Option<(BubbleKind, i32)> {
+        let number = self.numbers_caught.pop()?;
+        Some((self.caught_kinds.pop().unwrap_or(BubbleKind::Number), number))
+    }
+
+    /// Whether a broken objective is waiting to be undone by releasing the last catch. When
+    /// an overshoot would end the round, the player gets a few seconds to release first.
+    fn in_overshoot_grace(&mut self, now_seconds: u64) -> bool {
+        if self.rules.overshoot != OvershootRule::LoseLife || !self.level.release_allowed {
+            return false;
+        }
+        let until = match self.overshoot_grace_until_seconds {
+            Some(until) => until,
+            // No point waiting if the release won't be ready in time:
+            None if self.release_cooldown_remaining(now_seconds) >= constants::OVERSHOOT_GRACE_SECONDS => return false,
+            None => *self.overshoot_grace_until_seconds.insert(now_seconds + constants::OVERSHOOT_GRACE_SECONDS),
+        };
+        now_seconds < until
+    }
+
+    /// Lights up the bubbles that finish the round for a few seconds, at the cost of a point.
+    fn use_hint(&mut self, now_seconds: u64) {
+        if self.hints_used >= constants::HINTS_PER_ROUND {
+            return;
+        }
+        self.hints_used += 1;
+        self.score = self.score.saturating_sub(constants::HINT_SCORE_COST);
+        self.hint_until_seconds = Some(now_seconds + constants::HINT_DISPLAY_SECONDS);
+    }
+
+    /// While a hint is showing: the on-screen bubbles that complete the objective,
+    /// either on their own or together with one other bubble.
+    pub fn hinted_bubbles(&self, now_seconds: u64) -> Vec<u32> {
+        if self.hint_until_seconds.is_none_or(|until| now_seconds >= until) {
+            return vec![];
+        }
+        let progress = |numbers: &[i32]| {
+            let caught = [self.numbers_caught.as_slice(), numbers].concat();
+            self.level.objective.evaluate(&caught, self.current_target)
+        };
+        // Pairs are tried both ways round, as some objectives only check the latest catch:
+        let finishes_in_order = |first: i32, second: i32| {
+            progress(&[first]) == Progress::Incomplete && progress(&[first, second]) == Progress::Complete
+        };
+        let candidates: Vec<&Bubble> = self.bubbles.iter().filter(|b| b.kind == BubbleKind::Number).collect();
+        let mut hinted = vec![];
+        for (i, a) in candidates.iter().enumerate() {
+            let pairs_up = || {
+                candidates.iter().enumerate().any(|(j, b)| {
+                    i != j && (finishes_in_order(a.number, b.number) || finishes_in_order(b.number, a.number))
+                })
+            };
+            if progress(&[a.number]) == Progress::Complete || pairs_up() {
+                hinted.push(a.index);
+            }
+        }
+        hinted
     }
 
     fn handle_input(&mut self, ctx: &mut Context) {
-        match self.current_mode {
-            GameMode::Running => {
-                if ctx.keyboard.is_key_pressed(KeyCode::Left) && self.ship.position.x >= 5.0 {
-                    self.ship.position.x -= self.ship.speed.x;
-                }
-                if ctx.keyboard.is_key_pressed(KeyCode::Right)
-                    && self.ship.position.x <= (self.window_size.width - 15) as f32
-                {
-                    self.ship.position.x += self.ship.speed.x;
-                }
-                if ctx.keyboard.is_key_pressed(KeyCode::Up) && self.ship.position.y >= 0.0 {
-                    self.ship.position.y -= self.ship.speed.y;
-                }
-                if ctx.keyboard.is_key_pressed(KeyCode::Down)
-                    && self.ship.position.y <= (self.window_size.height - 25) as f32
-                {
-                    self.ship.position.y += self.ship.speed.y;
-                }
+        if matches!(self.current_mode, GameMode::Running) && !self.paused {
+            let mut direction = Vec2::ZERO;
+            if ctx.keyboard.is_key_pressed(self.settings.keys.left) && self.ship.position.x >= 5.0 {
+                direction.x -= 1.0;
+            }
+            if ctx.keyboard.is_key_pressed(self.settings.keys.right)
+                && self.ship.position.x <= (self.window_size.width - 15) as f32
+            {
+                direction.x += 1.0;
+            }
+            if ctx.keyboard.is_key_pressed(self.settings.keys.up) && self.ship.position.y >= 0.0 {
+                direction.y -= 1.0;
+            }
+            if ctx.keyboard.is_key_pressed(self.settings.keys.down)
+                && self.ship.position.y <= (self.window_size.height - 25) as f32
+            {
+                direction.y += 1.0;
+            }
+            let movement = self.ship_movement(direction);
+
+            // Move each axis separately so the ship can still slide along a wall it's touching.
+            // Whatever stops the ship on an axis also stops it drifting that way:
+            let along_x = self.ship.position + Vec2::new(movement.x, 0.0);
+            if !self.ship_blocked_at(along_x) && self.ship_in_bounds(along_x) {
+                self.ship.position = along_x;
+            } else {
+                self.ship.velocity.x = 0.0;
+            }
+            let along_y = self.ship.position + Vec2::new(0.0, movement.y);
+            if !self.ship_blocked_at(along_y) && self.ship_in_bounds(along_y) {
+                self.ship.position = along_y;
+            } else {
+                self.ship.velocity.y = 0.0;
             }
-            _ => ()
         }
     }
 
     fn process_timer(&mut self, ctx: &Context) {
        // println!("Time: {}",ctx.time.time_since_start().as_millis() );
         let time_elapsed = ctx.time.time_since_start().as_secs() - self.round_start_time_seconds;
-        self.round_time_remaining_seconds = self.round_allowed_time_seconds - time_elapsed;
-        if self.round_time_remaining_seconds <= 0 {
-            self.deduct_life();
-            self.current_mode = GameMode::OutOfTimeScreen;
-            self.round_time_remaining_seconds = 0;
+        self.round_time_remaining_seconds = self
+            .round_allowed_time_seconds
+            .saturating_sub(time_elapsed + self.round_time_penalty_seconds);
+        if self.round_time_remaining_seconds == 0 {
+            self.log_round(RoundOutcome::TimedOut, ctx.time.time_since_start().as_secs());
+            if let Clock::Global { .. } = self.rules.clock {
+                self.record_high_score();
+                self.current_mode = GameMode::TimeUpScreen;
+            } else {
+                self.stats.timed_out();
+                self.win_streak = 0;
+                self.achievement_event(GameEvent::TimedOut);
+                self.deduct_life();
+                self.current_mode = GameMode::OutOfTimeScreen;
+            }
+        }
+    }
+
+    /// Returns false if the lives were already at the maximum.
+    fn gain_life(&mut self) -> bool {
+        let lives = self.rules.lives.gain_life(self.lives_remaining);
+        let gained = lives > self.lives_remaining;
+        self.lives_remaining = lives;
+        gained
+    }
+
+    /// Gives a life for each extra-life score reached. Points lost afterwards don't take it back.
+    fn check_extra_lives(&mut self) {
+        let Some(every) = self.rules.lives.points_per_extra_life() else {
+            return;
+        };
+        while self.score >= self.next_extra_life_score {
+            self.next_extra_life_score += every;
+            if self.gain_life() {
+                self.extra_life_earned = true;
+            }
         }
     }
 
     fn deduct_life(&mut self) {
-        if self.lives_remaining > 0 {
-            self.lives_remaining -= 1;
+        self.lives_remaining = self.rules.lives.lose_life(self.lives_remaining);
+    }
+
+    /// The catches broke the round's objective, `reason` explains how.
+    fn handle_overshoot(&mut self, reason: String, now_seconds: u64) {
+        self.mistakes_this_round += 1;
+        self.stats.overshot();
+        self.win_streak = 0;
+        self.achievement_event(GameEvent::Overshot);
+        match self.rules.overshoot {
+            OvershootRule::LoseLife => {
+                self.log_round(RoundOutcome::Overshot, now_seconds);
+                self.score = self.score.saturating_sub(self.rules.scoring.overshoot_penalty());
+                self.deduct_life();
+                self.overshoot_reason = reason;
+                self.current_mode = GameMode::OvershotScreen;
+            }
+            OvershootRule::Feedback => {
+                // Take the offending catch back off and explain what went wrong instead:
+                self.take_back_last_catch();
+                self.show_feedback(reason, now_seconds);
+            }
+            OvershootRule::LoseSeconds(seconds) => {
+                self.take_back_last_catch();
+                self.round_time_penalty_seconds += seconds;
+                self.show_feedback(format!("Overshot! -{}s", seconds), now_seconds);
+            }
         }
     }
 
+    fn show_feedback(&mut self, message: String, now_seconds: u64) {
+        self.feedback_message = Some(message);
+        self.feedback_until_seconds = now_seconds + constants::FEEDBACK_DISPLAY_SECONDS;
+    }
+
     fn handle_win(&mut self) {
-        self.score += 1;
-        self.round_time_bonus = (self.round_time_remaining_seconds / constants::SECONDS_LEFT_PER_BONUS_POINT) as u32;
-        self.score += self.round_time_bonus;
+        if self.campaign_level_index().is_some() {
+            self.last_round_stars = campaign::rate(
+                &self.level,
+                self.round_time_remaining_seconds,
+                self.numbers_caught.len(),
+            );
+            if self.campaign_progress.record(&self.level, self.last_round_stars) {
+                persistence::save(
+                    &self.profile_path(constants::CAMPAIGN_SAVE_FILE),
+                    &self.campaign_progress,
+                );
+            }
+        }
+        if self.play_mode == PlayMode::Curriculum {
+            self.curriculum_progress.round_cleared(self.mistakes_this_round);
+            persistence::save(&self.profile_path(constants::CURRICULUM_SAVE_FILE), &self.curriculum_progress);
+        }
+        let seconds_left = (self.rules.clock != Clock::Off).then_some(self.round_time_remaining_seconds);
+        self.stats.round_won(self.current_target, seconds_left);
+        self.achievement_event(GameEvent::RoundWon {
+            target: self.current_target,
+            bubbles: self.numbers_caught.len(),
+            seconds_left,
+        });
+        self.win_streak = if self.mistakes_this_round == 0 { self.win_streak + 1 } else { 0 };
+        self.score_breakdown = self.rules.scoring.score_win(&WinInfo {
+            seconds_left,
+            bubbles: self.numbers_caught.len(),
+            streak: self.win_streak,
+            overshoots: self.mistakes_this_round,
+        });
+        self.score += self.score_breakdown.total();
+        self.targets_hit += 1;
+        self.check_extra_lives();
         self.current_mode = GameMode::WinScreen;
+
+        if !self.rules.progression.breaks_between_rounds() {
+            self.prepare_next_round();
+            self.current_mode = GameMode::Running;
+        }
+    }
+
+    /// Puts everything back to how it is at the start of a new game.
+    fn reset_game(&mut self) {
+        self.score = 0;
+        self.targets_hit = 0;
+        self.win_streak = 0;
+        self.recent_targets.clear();
+        self.current_round = 0;
+        self.round_allowed_time_seconds = constants::STARTING_ROUND_TIME_SECONDS;
+        self.round_time_penalty_seconds = 0;
+        self.play_mode = PlayMode::Endless;
+        self.rules = Rules::standard();
+        self.rng = ChaCha8Rng::from_entropy();
+        self.feedback_message = None;
+        self.intro_message = None;
+        self.session_started_at = session::now();
+    }
+
+    /// Adds the round that just ended to the session log for the teacher/parent report.
+    fn log_round(&mut self, outcome: RoundOutcome, now_seconds: u64) {
+        let round = RoundLog {
+            round: self.current_round,
+            target: self.current_target,
+            objective: self.level.objective.describe(),
+            caught: self.numbers_caught.clone(),
+            overshoots: self.mistakes_this_round,
+            outcome,
+            seconds: now_seconds.saturating_sub(self.round_log_start_seconds),
+        };
+        self.session_log.record(self.session_started_at, self.play_mode, round);
+        persistence::save(&self.profile_path(constants::SESSIONS_SAVE_FILE), &self.session_log);
+        self.save_stats();
+        // Time attack goes straight on to the next target, so that round starts now:
+        self.round_log_start_seconds = now_seconds;
+    }
+
+    /// Out of lives. A scored daily challenge gets its final score saved here.
+    fn game_over(&mut self) {
+        if let PlayMode::Daily { scored: true } = self.play_mode {
+            self.daily_record.finish_attempt(self.daily_day, self.score);
+            persistence::save(&self.profile_path(constants::DAILY_SAVE_FILE), &self.daily_record);
+        }
+        self.record_high_score();
+        self.current_mode = GameMode::DeathScreen;
+    }
+
+    fn open_daily_screen(&mut self) {
+        self.reset_game();
+        self.daily_day = daily::today();
+        self.current_mode = GameMode::DailyScreen;
+    }
+
+    /// Starts today's challenge. The first go of the day uses up the scored attempt.
+    fn start_daily(&mut self) {
+        let scored = !self.daily_record.has_played(self.daily_day);
+        if scored {
+            self.daily_record.start_attempt(self.daily_day);
+            persistence::save(&self.profile_path(constants::DAILY_SAVE_FILE), &self.daily_record);
+        }
+        self.reset_game();
+        self.play_mode = PlayMode::Daily { scored };
+        self.prepare_next_round();
+    }
+
+    fn open_zen_setup(&mut self) {
+        self.reset_game();
+        self.current_mode = GameMode::ZenSetupScreen;
+    }
+
+    fn start_zen(&mut self) {
+        self.save_settings();
+        self.reset_game();
+        self.play_mode = PlayMode::Zen;
+        self.rules = Rules::zen();
+        self.prepare_next_round();
+    }
+
+    fn export_progress_report(&mut self) {
+        let path = self.profile_path(constants::PROGRESS_REPORT_FILE);
+        self.intro_message = Some(if persistence::export(&path, &self.fact_book.report()) {
+            format!("Progress report saved to {}", path.display())
+        } else {
+            "Couldn't save the progress report".to_string()
+        });
+    }
+
+    fn start_curriculum(&mut self) {
+        self.reset_game();
+        self.play_mode = PlayMode::Curriculum;
+        self.rules = Rules::curriculum();
+        self.prepare_next_round();
+    }
+
+    fn start_countdown(&mut self) {
+        self.reset_game();
+        self.play_mode = PlayMode::Countdown;
+        self.rules = Rules::countdown();
+        self.prepare_next_round();
+    }
+
+    fn start_time_attack(&mut self) {
+        self.reset_game();
+        self.play_mode = PlayMode::TimeAttack;
+        self.rules = Rules::time_attack();
+        if let Clock::Global { seconds } = self.rules.clock {
+            self.round_allowed_time_seconds = seconds;
+            self.round_time_remaining_seconds = seconds;
+        }
+        self.prepare_next_round();
+    }
+
+    /// Starts a single campaign level picked on the level select screen.
+    fn start_campaign_level(&mut self, index: usize) {
+        if !self.campaign_progress.is_unlocked(&self.campaign_levels, index) {
+            return;
+        }
+        self.reset_game();
+        self.play_mode = PlayMode::Campaign { level_index: index };
+        self.rules = Rules::campaign(index);
+        self.prepare_next_round();
+    }
+
+    fn open_level_select(&mut self) {
+        self.reset_game();
+        self.current_mode = GameMode::LevelSelectScreen;
+    }
+
+    /// What space does once a round is over: campaign levels go back to level select
+    /// (moving on to the next level after a win), endless play carries on.
+    fn continue_after_round(&mut self) {
+        if let Some(index) = self.campaign_level_index() {
+            if matches!(self.current_mode, GameMode::WinScreen) && index + 1 < self.campaign_levels.len() {
+                self.level_select_cursor = index + 1;
+            }
+            self.open_level_select();
+        } else if self.rules.lives.is_out(self.lives_remaining) {
+            self.game_over();
+        } else {
+            self.prepare_next_round();
+        }
     }
 
     fn prepare_next_round(&mut self) {
-        self.current_target = Self::random_target();
         self.bubbles = vec![];
         self.numbers_caught = vec![];
-
-        self.round_time_remaining_seconds = self.round_allowed_time_seconds;
+        self.caught_kinds = vec![];
+        self.last_release_time_seconds = None;
+        self.overshoot_grace_until_seconds = None;
+        self.hints_used = 0;
+        self.hint_until_seconds = None;
+        self.mistakes_this_round = 0;
+        self.round_spawns = 0;
         self.current_round += 1;
-        if self.round_allowed_time_seconds > constants::MIN_ROUND_TIME_SECONDS {
-            self.round_allowed_time_seconds -= constants::TIME_DEDUCTED_PER_ROUND;
+        if self.current_round == 1 {
+            self.lives_remaining = self.rules.lives.starting_lives();
+            self.next_extra_life_score = self.rules.lives.points_per_extra_life().unwrap_or(0);
+            self.stats.game_started();
+            self.save_stats();
+        }
+        self.achievement_event(GameEvent::RoundStarted { round: self.current_round });
+        self.fact_book.next_round();
+        persistence::save(&self.profile_path(constants::FACTS_SAVE_FILE), &self.fact_book);
+
+        if let PlayMode::Daily { .. } = self.play_mode {
+            // Everything random about the round comes from the date, so it's the same for everyone:
+            self.rng = ChaCha8Rng::seed_from_u64(daily::seed_for(self.daily_day, self.current_round));
         }
-        
-        self.round_time_bonus = 0;
+
+        let targets = self.rules.targets.clone();
+        let round_seconds = self.rules.progression.round_seconds(self.current_round, self.round_allowed_time_seconds);
+        self.level = targets.next_level(&mut RoundSetup {
+            round: self.current_round,
+            round_seconds,
+            difficulty: self.difficulty(),
+            recent_targets: &self.recent_targets,
+            rng: &mut self.rng,
+            settings: &self.settings,
+            curriculum: &self.curriculum_progress,
+            campaign_levels: &self.campaign_levels,
+        });
+        self.round_allowed_time_seconds = self.level.time_limit_seconds;
+        if self.settings.negative_bubbles
+            && !matches!(self.play_mode, PlayMode::Campaign { .. } | PlayMode::Daily { .. } | PlayMode::Curriculum)
+        {
+            self.level.negative_probability = constants::NEGATIVE_BUBBLE_PROBABILITY;
+        }
+        self.current_target = self.level.target;
+        self.ship.velocity = Vec2::ZERO;
+        self.recent_targets.push_back(self.current_target);
+        if self.recent_targets.len() > constants::RECENT_TARGETS_REMEMBERED {
+            self.recent_targets.pop_front();
+        }
+        self.spawner = match self.play_mode {
+            PlayMode::Daily { .. } => Box::new(DailySpawner),
+            _ => spawner::for_level(&self.level, self.curriculum_progress.stage()),
+        };
+        // A global clock keeps running across rounds, a per-round one starts afresh:
+        if !matches!(self.rules.clock, Clock::Global { .. }) {
+            self.round_time_remaining_seconds = self.round_allowed_time_seconds;
+            self.round_time_penalty_seconds = 0;
+        }
+        self.place_walls();
+
+        self.score_breakdown = ScoreBreakdown::default();
+        self.extra_life_earned = false;
+        self.last_round_stars = 0;
         self.current_mode = GameMode::NextRoundScreen;
     }
 }
@@ -199,31 +1022,50 @@ impl GameState {
 impl EventHandler for GameState {
     fn update(&mut self, ctx: &mut Context) -> GameResult {
         self.handle_input(ctx);
+        self.update_toasts(ctx.time.time_since_start().as_secs());
 
         if self.paused || !matches!(self.current_mode, GameMode::Running) {
             return Ok(());
         }
 
-        self.process_timer(ctx);
+        if self.rules.clock != Clock::Off {
+            self.process_timer(ctx);
+        }
+        if !matches!(self.current_mode, GameMode::Running) {
+            return Ok(());
+        }
 
-        if ctx.time.ticks() % constants::NEW_BUBBLE_INTERVAL_FRAMES == 0 {
+        if ctx.time.ticks().is_multiple_of(self.level.spawn_interval_frames) {
             self.add_bubble();
         }
 
         self.update_bubbles();
-        self.check_bubble_caught();
-
-        let current_sum: u32 = self.compute_caught_sum();
+        // Nothing more is caught while an overshoot is waiting to be released:
+        if self.overshoot_grace_until_seconds.is_none() {
+            self.check_bubble_caught(ctx.time.time_since_start().as_secs());
+        }
+        if !matches!(self.current_mode, GameMode::Running) {
+            return Ok(());
+        }
 
-        match current_sum.cmp(&self.current_target) {
-            Ordering::Less => (),
-            Ordering::Greater => {
-                // Overshot
-                self.deduct_life();
-                self.current_mode = GameMode::OvershotScreen;
+        match self.level.objective.evaluate(&self.numbers_caught, self.current_target) {
+            Progress::Incomplete => (),
+            Progress::Failed(_)
+                if self.level.negative_probability > 0.0
+                    && self.level.objective.can_still_recover(&self.numbers_caught, self.current_target) =>
+            {
+                // Over the target, but a negative bubble caught soon enough can still bring the total back
+            }
+            Progress::Failed(_) if self.in_overshoot_grace(ctx.time.time_since_start().as_secs()) => {
+                // Over the target, but releasing the last catch can still undo it
+            }
+            Progress::Failed(reason) => {
+                // Overshot, or otherwise broke the objective
+                self.handle_overshoot(reason, ctx.time.time_since_start().as_secs());
             }
-            Ordering::Equal => {
+            Progress::Complete => {
                 // Win!
+                self.log_round(RoundOutcome::Cleared, ctx.time.time_since_start().as_secs());
                self.handle_win();
             }
         }
@@ -232,7 +1074,12 @@ impl EventHandler for GameState {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        return renderer::render(self, ctx);
+        renderer::render(self, ctx)
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        self.type_character(character);
+        Ok(())
     }
 
     fn key_down_event(
@@ -241,45 +1088,143 @@ impl EventHandler for GameState {
         input: KeyInput,
         _repeat: bool,
     ) -> GameResult {
+        let Some(keycode) = input.keycode else {
+            return Ok(());
+        };
         match self.current_mode {
             GameMode::IntroScreen => {
-                // Spacebar goes to the 'next round' screen:
-                if input.keycode.unwrap() == KeyCode::Space {
+                // Spacebar goes to the 'next round' screen, L opens the campaign:
+                if keycode == KeyCode::Space {
                     self.prepare_next_round();
                 }
+                if keycode == KeyCode::L && !self.campaign_levels.is_empty() {
+                    self.open_level_select();
+                }
+                if keycode == KeyCode::D {
+                    self.open_daily_screen();
+                }
+                if keycode == KeyCode::Z {
+                    self.open_zen_setup();
+                }
+                if keycode == KeyCode::T {
+                    self.start_time_attack();
+                }
+                if keycode == KeyCode::C {
+                    self.start_countdown();
+                }
+                if keycode == KeyCode::M {
+                    self.start_curriculum();
+                }
+                if keycode == KeyCode::E {
+                    self.export_progress_report();
+                }
+                if keycode == KeyCode::N {
+                    self.settings.negative_bubbles = !self.settings.negative_bubbles;
+                    self.save_settings();
+                }
+                if keycode == KeyCode::Tab {
+                    self.settings.difficulty = self.settings.difficulty.next();
+                    self.save_settings();
+                }
+                if keycode == KeyCode::I {
+                    self.settings.arcade_movement = !self.settings.arcade_movement;
+                    self.save_settings();
+                }
+                if keycode == KeyCode::P {
+                    self.open_profiles();
+                }
+                if keycode == KeyCode::S {
+                    self.current_mode = GameMode::StatsScreen;
+                }
+                if keycode == KeyCode::A {
+                    self.current_mode = GameMode::AchievementsScreen;
+                }
             },
-            GameMode::OvershotScreen | GameMode::OutOfTimeScreen => {
-                // Spacebar goes to the 'next round' screen, or Death if no more lives left.
-                if input.keycode.unwrap() == KeyCode::Space {
-                    if self.lives_remaining <= 0 {
-                        self.current_mode = GameMode::DeathScreen;
-                    } else {
-                        self.prepare_next_round();
-                    }
+            GameMode::StatsScreen | GameMode::AchievementsScreen => {
+                if keycode == KeyCode::Escape || keycode == KeyCode::Space {
+                    self.current_mode = GameMode::IntroScreen;
                 }
             },
-            GameMode::DeathScreen => {
-                if input.keycode.unwrap() == KeyCode::Space {
-                    self.score = 0;
-                    self.lives_remaining = STARTING_LIVES;
+            GameMode::ProfilesScreen => self.profiles_key(keycode),
+            GameMode::KeyBindingsScreen => self.key_bindings_key(keycode),
+            GameMode::TimeUpScreen => {
+                if keycode == KeyCode::Space {
+                    self.reset_game();
                     self.current_mode = GameMode::IntroScreen;
                 }
             },
-            GameMode::WinScreen => {
-                if input.keycode.unwrap() == KeyCode::Space {
-                    self.prepare_next_round();
+            GameMode::ZenSetupScreen => match keycode {
+                KeyCode::Up => self.settings.zen_target_range = self.settings.zen_target_range.saturating_sub(1),
+                KeyCode::Down => {
+                    self.settings.zen_target_range = (self.settings.zen_target_range + 1).min(rules::ZEN_TARGET_RANGES.len() - 1)
+                }
+                KeyCode::Left => self.settings.zen_speed = self.settings.zen_speed.saturating_sub(1),
+                KeyCode::Right => self.settings.zen_speed = (self.settings.zen_speed + 1).min(rules::ZEN_SPEEDS.len() - 1),
+                KeyCode::Space | KeyCode::Return => self.start_zen(),
+                KeyCode::Escape => self.current_mode = GameMode::IntroScreen,
+                _ => (),
+            },
+            GameMode::DailyScreen => match keycode {
+                KeyCode::Space | KeyCode::Return => self.start_daily(),
+                KeyCode::Escape => self.current_mode = GameMode::IntroScreen,
+                _ => (),
+            },
+            GameMode::LevelSelectScreen => match keycode {
+                KeyCode::Up => self.level_select_cursor = self.level_select_cursor.saturating_sub(1),
+                KeyCode::Down => {
+                    self.level_select_cursor = (self.level_select_cursor + 1).min(self.campaign_levels.len() - 1)
+                }
+                KeyCode::Space | KeyCode::Return => self.start_campaign_level(self.level_select_cursor),
+                KeyCode::Escape => self.current_mode = GameMode::IntroScreen,
+                _ => (),
+            },
+            GameMode::OvershotScreen | GameMode::OutOfTimeScreen | GameMode::WinScreen => {
+                // Spacebar goes to the 'next round' screen, or Death if no more lives left.
+                if keycode == KeyCode::Space {
+                    self.continue_after_round();
+                }
+            },
+            GameMode::DeathScreen => {
+                if keycode == KeyCode::Space {
+                    if self.campaign_level_index().is_some() {
+                        self.open_level_select();
+                    } else if let PlayMode::Daily { .. } = self.play_mode {
+                        self.open_daily_screen();
+                    } else {
+                        self.reset_game();
+                        self.current_mode = GameMode::IntroScreen;
+                    }
                 }
             },
             GameMode::Running => {
-                if input.keycode.unwrap() == KeyCode::P {
+                if keycode == self.settings.keys.pause {
                     self.paused = !self.paused;
                 }
+                // R throws the last caught number back into the field:
+                if keycode == self.settings.keys.release && !self.paused {
+                    self.release_last_caught(ctx.time.time_since_start().as_secs());
+                }
+                if keycode == self.settings.keys.hint && !self.paused {
+                    self.use_hint(ctx.time.time_since_start().as_secs());
+                }
+                // Zen and curriculum have no way to lose, so escape is the way out:
+                if keycode == KeyCode::Escape && matches!(self.play_mode, PlayMode::Zen | PlayMode::Curriculum) {
+                    self.log_round(RoundOutcome::Abandoned, ctx.time.time_since_start().as_secs());
+                }
+                if keycode == KeyCode::Escape && self.play_mode == PlayMode::Zen {
+                    self.open_zen_setup();
+                }
+                if keycode == KeyCode::Escape && self.play_mode == PlayMode::Curriculum {
+                    self.reset_game();
+                    self.current_mode = GameMode::IntroScreen;
+                }
             },
-            _ => {
+            GameMode::NextRoundScreen => {
                 // Spacebar starts the game:
-                if input.keycode.unwrap() == KeyCode::Space {
+                if keycode == KeyCode::Space {
                     self.current_mode = GameMode::Running;
                     self.round_start_time_seconds = ctx.time.time_since_start().as_secs();
+                    self.round_log_start_seconds = self.round_start_time_seconds;
                 }
             }
         }
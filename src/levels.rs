@@ -0,0 +1,229 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::constants;
+use crate::curriculum::{Stage, ROUNDS_TO_MASTER};
+use crate::movement::{MovementPattern, PatternWeight, PATTERN_TABLE};
+use crate::objectives::Objective;
+use crate::spawner::SpawnStrategy;
+use crate::structs::MinePenalty;
+
+/// Everything that shapes a single round: the target, how long you get and what comes down.
+/// Authored levels are loaded from TOML files in the levels directory; endless play builds
+/// one of these per round from the constants instead.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Level {
+    /// Where the level was loaded from, e.g. "1_basics/01_first_steps". Used as its save key.
+    #[serde(skip)]
+    pub id: String,
+    /// Display name of the world (levels subdirectory) the level belongs to.
+    #[serde(skip)]
+    pub world: String,
+    pub name: String,
+    pub description: String,
+    pub target: u32,
+    pub objective: Objective,
+    pub time_limit_seconds: u64,
+    /// Numbers to spawn in order, repeating once exhausted. Takes priority over `spawn_table`.
+    pub bubble_sequence: Vec<i32>,
+    /// Weighted numbers to pick from when there's no fixed sequence.
+    pub spawn_table: Vec<SpawnWeight>,
+    /// How generated bubbles pick their numbers.
+    pub spawner: SpawnStrategy,
+    /// Frames between new bubbles, lower is more bubbles.
+    pub spawn_interval_frames: usize,
+    /// How many generated bubbles in a row are guaranteed to be able to finish the round. 0 turns this off.
+    pub plan_window: usize,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Movement pattern mix. Falls back to the endless PATTERN_TABLE when empty.
+    pub patterns: Vec<PatternWeight>,
+    pub mine_probability: f64,
+    pub decoy_probability: f64,
+    /// Chance of a heart bubble, worth an extra life, in modes that have lives.
+    pub heart_probability: f64,
+    /// Chance of a generated bubble being negative, for objectives that only care about the total.
+    pub negative_probability: f64,
+    pub mine_penalty: MinePenalty,
+    pub walls: usize,
+    pub release_allowed: bool,
+    /// Finishing with this many catches or fewer earns a star.
+    pub par_catches: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnWeight {
+    pub number: i32,
+    pub weight: u32,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            world: String::new(),
+            name: String::new(),
+            description: String::new(),
+            target: 0,
+            objective: Objective::Sum,
+            time_limit_seconds: constants::STARTING_ROUND_TIME_SECONDS,
+            bubble_sequence: vec![],
+            spawn_table: vec![],
+            spawner: SpawnStrategy::Shortfall,
+            spawn_interval_frames: constants::NEW_BUBBLE_INTERVAL_FRAMES,
+            plan_window: constants::SPAWN_PLAN_WINDOW,
+            min_speed: constants::MIN_BUBBLE_SPEED,
+            max_speed: constants::MAX_BUBBLE_SPEED,
+            patterns: vec![],
+            mine_probability: 0.0,
+            decoy_probability: 0.0,
+            heart_probability: 0.0,
+            negative_probability: 0.0,
+            mine_penalty: MinePenalty::Seconds(constants::MINE_TIME_PENALTY_SECONDS),
+            walls: 0,
+            release_allowed: true,
+            par_catches: 3,
+        }
+    }
+}
+
+impl Level {
+    /// The randomly generated round used by endless play.
+    pub fn endless(round: u32, target: u32, objective: Objective, time_limit_seconds: u64) -> Self {
+        let hazards = round >= constants::HAZARDS_FROM_ROUND;
+        Self {
+            name: format!("Round {}", round),
+            target,
+            objective,
+            time_limit_seconds,
+            min_speed: constants::MIN_BUBBLE_SPEED + (round as f32 / 2.0),
+            max_speed: constants::MAX_BUBBLE_SPEED + (round as f32 / 2.0),
+            mine_probability: if hazards { constants::MINE_PROBABILITY } else { 0.0 },
+            decoy_probability: if hazards { constants::DECOY_PROBABILITY } else { 0.0 },
+            heart_probability: constants::HEART_BUBBLE_PROBABILITY,
+            mine_penalty: if round >= constants::MINES_COST_LIFE_FROM_ROUND {
+                MinePenalty::Life
+            } else {
+                MinePenalty::Seconds(constants::MINE_TIME_PENALTY_SECONDS)
+            },
+            walls: if round >= constants::WALLS_FROM_ROUND { constants::WALLS_PER_ROUND } else { 0 },
+            ..Self::default()
+        }
+    }
+
+    /// A zen practice round: no hazards, and the player's chosen bubble speed.
+    pub fn zen(target: u32, min_speed: f32, max_speed: f32) -> Self {
+        Self {
+            name: "Zen".to_string(),
+            target,
+            min_speed,
+            max_speed,
+            ..Self::default()
+        }
+    }
+
+    /// A curriculum round: slow, no hazards, and named after the stage being practised.
+    pub fn curriculum(stage: &Stage, target: u32) -> Self {
+        Self {
+            name: stage.name.to_string(),
+            description: format!("Clear {} rounds in a row without overshooting to move on", ROUNDS_TO_MASTER),
+            target,
+            min_speed: 1.0,
+            max_speed: 1.8,
+            patterns: vec![PatternWeight { pattern: MovementPattern::Straight, min_round: 0, weight: 1 }],
+            spawner: SpawnStrategy::Curriculum,
+            ..Self::default()
+        }
+    }
+
+    pub fn pattern_table(&self) -> &[PatternWeight] {
+        if self.patterns.is_empty() {
+            PATTERN_TABLE
+        } else {
+            &self.patterns
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let level: Level = toml::from_str(source).map_err(|e| e.to_string())?;
+        if !level.objective.is_achievable(level.target) {
+            return Err(format!("target {} can't be reached with objective {:?}", level.target, level.objective));
+        }
+        if level.negative_probability > 0.0 && !level.objective.allows_negatives() {
+            return Err(format!("objective {:?} can't have negative bubbles", level.objective));
+        }
+        let has_negative_numbers =
+            level.bubble_sequence.iter().any(|n| *n < 0) || level.spawn_table.iter().any(|s| s.number < 0);
+        if has_negative_numbers && !level.objective.allows_negatives() {
+            return Err(format!("objective {:?} can't have negative bubbles", level.objective));
+        }
+        if level.spawn_interval_frames == 0 {
+            return Err("spawn_interval_frames has to be at least 1".to_string());
+        }
+        if level.min_speed <= 0.0 {
+            return Err("min_speed has to be more than 0".to_string());
+        }
+        if level.min_speed > level.max_speed {
+            return Err("min_speed can't be more than max_speed".to_string());
+        }
+        if level.bubble_sequence.contains(&0) || level.spawn_table.iter().any(|s| s.number == 0) {
+            return Err("bubble numbers can't be 0".to_string());
+        }
+        Ok(level)
+    }
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok().map(|e| e.path())).collect(),
+        Err(_) => vec![],
+    };
+    paths.sort();
+    paths
+}
+
+/// Turns a directory name like "2_hazards" into "Hazards".
+fn world_name(dir: &Path) -> String {
+    let stem = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let words = stem.trim_start_matches(|c: char| c.is_ascii_digit() || c == '_').replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => stem,
+    }
+}
+
+/// Loads every `.toml` file in the directory, in file name order.
+/// Files that fail to parse are reported and skipped rather than stopping the game.
+fn load_world(root: &Path, dir: &Path, world: &str) -> Vec<Level> {
+    let mut levels = vec![];
+    for path in sorted_entries(dir) {
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|s| Level::parse(&s)) {
+            Ok(mut level) => {
+                let relative = path.strip_prefix(root).unwrap_or(&path).with_extension("");
+                level.id = relative.to_string_lossy().replace('\\', "/");
+                level.world = world.to_string();
+                levels.push(level);
+            }
+            Err(e) => eprintln!("Skipping level {}: {}", path.display(), e),
+        }
+    }
+    levels
+}
+
+/// Loads the campaign: each subdirectory of the levels directory is a world, played in
+/// directory name order. Loose files directly in the levels directory come first.
+pub fn load_campaign(root: &Path) -> Vec<Level> {
+    let mut levels = load_world(root, root, "Levels");
+    for dir in sorted_entries(root).into_iter().filter(|p| p.is_dir()) {
+        let world = world_name(&dir);
+        levels.extend(load_world(root, &dir, &world));
+    }
+    levels
+}
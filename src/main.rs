@@ -1,14 +1,65 @@
 use crate::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::profiles::ProfileIndex;
+use crate::session::SessionLog;
 use crate::structs::GameState;
 use ggez::event::{self};
 use ggez::{ContextBuilder, conf};
 
+mod achievements;
+mod campaign;
 mod constants;
+mod curriculum;
+mod daily;
+mod difficulty;
+mod facts;
 mod game;
+mod levels;
+mod movement;
+mod objectives;
+mod persistence;
+mod planner;
+mod policies;
+mod profiles;
 mod renderer;
+mod rules;
+mod scoring;
+mod session;
+mod settings;
+mod spawner;
+mod stats;
 mod structs;
 
+/// `rust_bubbles export [html|csv] [profile]` writes a player's session report to their
+/// profile directory instead of starting the game. Defaults to the current profile.
+fn export_report(format: &str, profile: Option<&str>) {
+    let index = ProfileIndex::load();
+    let player = profile.unwrap_or(&index.current);
+    if !index.names.iter().any(|n| n == player) {
+        eprintln!("No profile called {}, the profiles are: {}", player, index.names.join(", "));
+        return;
+    }
+    let log: SessionLog = persistence::load_or_default(&profiles::profile_path(player, constants::SESSIONS_SAVE_FILE));
+    let (file_name, contents) = match format {
+        "csv" => ("report.csv", log.csv_report(player)),
+        "html" => ("report.html", log.html_report(player)),
+        _ => {
+            eprintln!("Unknown report format {}, use html or csv", format);
+            return;
+        }
+    };
+    let path = profiles::profile_path(player, file_name);
+    if persistence::export(&path, &contents) {
+        println!("Report written to {}", path.display());
+    }
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|a| a == "export") {
+        export_report(args.get(2).map(String::as_str).unwrap_or("html"), args.get(3).map(String::as_str));
+        return;
+    }
+
     let (ctx, event_loop) = ContextBuilder::new("NumberCatcher", "Flippie Scholtz")
         .window_setup(conf::WindowSetup::default().title("Rust Bubbles"))
         .window_mode(ggez::conf::WindowMode::default().dimensions(WINDOW_WIDTH, WINDOW_HEIGHT))
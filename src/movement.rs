@@ -0,0 +1,118 @@
+use ggez::glam::Vec2;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::constants::BUBBLE_RADIUS;
+use crate::structs::Bubble;
+
+/// How a bubble moves across the field once spawned.
+/// The base speed is picked by the spawner, the pattern decides what to do with it.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum MovementPattern {
+    /// Falls straight down at a constant speed.
+    Straight,
+    /// Falls while swaying left and right around the column it spawned in.
+    SineWave { amplitude: f32, frequency: f32 },
+    /// Falls at an angle, bouncing off the side walls.
+    Diagonal { drift: f32 },
+    /// Starts slow and keeps speeding up.
+    Accelerating { acceleration: f32 },
+    /// Alternates between falling and hanging in place.
+    Hovering { move_frames: u32, pause_frames: u32 },
+    /// Floats up from the bottom of the screen instead of falling.
+    Rising,
+}
+
+/// An entry in the pattern mix: patterns only appear from `min_round` onwards,
+/// and are picked in proportion to their weight among the patterns available that round.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatternWeight {
+    pub pattern: MovementPattern,
+    #[serde(default)]
+    pub min_round: u32,
+    pub weight: u32,
+}
+
+pub const PATTERN_TABLE: &[PatternWeight] = &[
+    PatternWeight { pattern: MovementPattern::Straight, min_round: 1, weight: 10 },
+    PatternWeight { pattern: MovementPattern::SineWave { amplitude: 40.0, frequency: 0.05 }, min_round: 2, weight: 3 },
+    PatternWeight { pattern: MovementPattern::Diagonal { drift: 0.6 }, min_round: 3, weight: 3 },
+    PatternWeight { pattern: MovementPattern::Accelerating { acceleration: 0.03 }, min_round: 4, weight: 2 },
+    PatternWeight { pattern: MovementPattern::Hovering { move_frames: 40, pause_frames: 25 }, min_round: 5, weight: 2 },
+    PatternWeight { pattern: MovementPattern::Rising, min_round: 6, weight: 2 },
+];
+
+pub fn choose_pattern(table: &[PatternWeight], round: u32, rng: &mut impl Rng) -> MovementPattern {
+    let available: Vec<&PatternWeight> = table
+        .iter()
+        .filter(|p| p.min_round <= round)
+        .collect();
+    let total_weight: u32 = available.iter().map(|p| p.weight).sum();
+    if total_weight == 0 {
+        return MovementPattern::Straight;
+    }
+
+    let mut pick = rng.gen_range(0..total_weight);
+    for entry in available {
+        if pick < entry.weight {
+            return entry.pattern;
+        }
+        pick -= entry.weight;
+    }
+    MovementPattern::Straight
+}
+
+impl MovementPattern {
+    /// Where along the y axis a bubble with this pattern enters the field.
+    pub fn start_y(&self, window_height: f32) -> f32 {
+        match self {
+            MovementPattern::Rising => window_height + BUBBLE_RADIUS,
+            _ => 0.0,
+        }
+    }
+
+    /// Turns a base falling speed into the bubble's starting velocity.
+    pub fn initial_speed(&self, base_speed: f32, rng: &mut impl Rng) -> Vec2 {
+        match self {
+            MovementPattern::Diagonal { drift } => {
+                let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                Vec2::new(direction * base_speed * drift, base_speed)
+            }
+            MovementPattern::Accelerating { .. } => Vec2::new(0.0, base_speed / 2.0),
+            MovementPattern::Rising => Vec2::new(0.0, -base_speed),
+            _ => Vec2::new(0.0, base_speed),
+        }
+    }
+
+    /// Advances a bubble by one frame.
+    pub fn step(&self, bubble: &mut Bubble, window_width: f32) {
+        bubble.age_frames += 1;
+        match *self {
+            MovementPattern::Straight | MovementPattern::Rising => {
+                bubble.position += bubble.speed;
+            }
+            MovementPattern::SineWave { amplitude, frequency } => {
+                bubble.position.y += bubble.speed.y;
+                bubble.position.x =
+                    bubble.anchor_x + amplitude * (bubble.age_frames as f32 * frequency).sin();
+            }
+            MovementPattern::Diagonal { .. } => {
+                bubble.position += bubble.speed;
+                if bubble.position.x < BUBBLE_RADIUS || bubble.position.x > window_width - BUBBLE_RADIUS {
+                    bubble.speed.x = -bubble.speed.x;
+                    bubble.position.x = bubble.position.x.clamp(BUBBLE_RADIUS, window_width - BUBBLE_RADIUS);
+                }
+            }
+            MovementPattern::Accelerating { acceleration } => {
+                bubble.speed.y += acceleration;
+                bubble.position += bubble.speed;
+            }
+            MovementPattern::Hovering { move_frames, pause_frames } => {
+                if bubble.age_frames % (move_frames + pause_frames) < move_frames {
+                    bubble.position += bubble.speed;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::constants;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Parity {
+    Odd,
+    Even,
+    Prime,
+}
+
+/// What the player has to do with their catches to clear a round.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "type")]
+pub enum Objective {
+    /// Add up to the target.
+    Sum,
+    /// Multiply up to the target.
+    Product,
+    /// Add up to the target using exactly this many bubbles.
+    ExactCount { count: usize },
+    /// Add up to the target using only odd, even or prime numbers.
+    Parity { parity: Parity },
+    /// Add up to the target without catching the same number twice.
+    NoRepeats,
+    /// Start at the target and subtract each catch, landing exactly on zero.
+    Countdown,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Progress {
+    Incomplete,
+    Complete,
+    /// The round can no longer be won with these catches. Holds an explanation for the player.
+    Failed(String),
+}
+
+/// The objectives endless play mixes in from OBJECTIVES_FROM_ROUND onwards.
+pub const VARIANT_OBJECTIVES: &[Objective] = &[
+    Objective::Product,
+    Objective::ExactCount { count: 3 },
+    Objective::Parity { parity: Parity::Odd },
+    Objective::Parity { parity: Parity::Even },
+    Objective::Parity { parity: Parity::Prime },
+    Objective::NoRepeats,
+];
+
+pub fn is_prime(n: u32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+fn sum_progress(caught: &[i32], target: u32) -> Progress {
+    let total: i32 = caught.iter().sum();
+    let target = target as i32;
+    if total < target {
+        return Progress::Incomplete;
+    }
+    if total == target {
+        return Progress::Complete;
+    }
+    let last = caught.last().copied().unwrap_or(0);
+    Progress::Failed(format!(
+        "{} + {} = {}, that's {} over {}",
+        total - last,
+        last,
+        total,
+        total - target,
+        target
+    ))
+}
+
+/// Writes out a running calculation, showing negative numbers as subtractions and vice versa.
+fn signed_terms(start: String, numbers: &[i32], subtract: bool) -> String {
+    numbers.iter().fold(start, |text, n| {
+        let minus = (*n < 0) != subtract;
+        let sign = if minus { "-" } else { "+" };
+        if text.is_empty() {
+            if minus { format!("-{}", n.abs()) } else { n.to_string() }
+        } else {
+            format!("{} {} {}", text, sign, n.abs())
+        }
+    })
+}
+
+impl Parity {
+    pub fn allows(&self, number: i32) -> bool {
+        match self {
+            Parity::Odd => number % 2 != 0,
+            Parity::Even => number % 2 == 0,
+            Parity::Prime => number > 0 && is_prime(number as u32),
+        }
+    }
+
+    /// Whether numbers of this kind can add up to exactly `left`. Any total but 1 can be
+    /// made from 2s and 3s, so prime sums only get stuck when 1 is left.
+    fn can_make(&self, left: i32) -> bool {
+        match self {
+            Parity::Odd => left >= 0,
+            Parity::Even => left >= 0 && left % 2 == 0,
+            Parity::Prime => left >= 0 && left != 1,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Parity::Odd => "odd",
+            Parity::Even => "even",
+            Parity::Prime => "prime",
+        }
+    }
+}
+
+impl Objective {
+    pub fn evaluate(&self, caught: &[i32], target: u32) -> Progress {
+        match *self {
+            Objective::Sum => sum_progress(caught, target),
+            Objective::Product => {
+                let product: i64 = caught.iter().map(|n| *n as i64).product();
+                if product == target as i64 {
+                    Progress::Complete
+                } else if product <= 0 || !(target as u64).is_multiple_of(product as u64) {
+                    Progress::Failed(format!("{} doesn't go into {}", product, target))
+                } else {
+                    Progress::Incomplete
+                }
+            }
+            Objective::ExactCount { count } => match sum_progress(caught, target) {
+                Progress::Complete if caught.len() == count => Progress::Complete,
+                Progress::Complete => Progress::Failed(format!("Hit {} with {} bubbles, needed {}", target, caught.len(), count)),
+                Progress::Incomplete if caught.len() >= count => {
+                    Progress::Failed(format!("Used all {} bubbles without reaching {}", count, target))
+                }
+                progress => progress,
+            },
+            Objective::Parity { parity } => match caught.last() {
+                Some(last) if !parity.allows(*last) => Progress::Failed(format!("{} isn't {}", last, parity.name())),
+                _ => {
+                    let left = target as i32 - caught.iter().sum::<i32>();
+                    match sum_progress(caught, target) {
+                        Progress::Incomplete if !parity.can_make(left) => {
+                            Progress::Failed(format!("{} left, and no {} numbers add up to that", left, parity.name()))
+                        }
+                        progress => progress,
+                    }
+                }
+            },
+            Objective::NoRepeats => match caught.split_last() {
+                Some((last, earlier)) if earlier.contains(last) => {
+                    Progress::Failed(format!("Already caught a {}", last))
+                }
+                _ => sum_progress(caught, target),
+            },
+            Objective::Countdown => {
+                let left = target as i32 - caught.iter().sum::<i32>();
+                match left {
+                    1.. => Progress::Incomplete,
+                    0 => Progress::Complete,
+                    _ => {
+                        let last = caught.last().copied().unwrap_or(0);
+                        Progress::Failed(format!("{} - {} = {}, that's below zero", left + last, last, left))
+                    }
+                }
+            }
+        }
+    }
+
+    /// The catches so far written out as a calculation, e.g. "5 + 3" or "17 - 5 - 3".
+    pub fn working_text(&self, caught: &[i32], target: u32) -> String {
+        match self {
+            Objective::Product => caught.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" x "),
+            Objective::Countdown => signed_terms(target.to_string(), caught, true),
+            _ => signed_terms(String::new(), caught, false),
+        }
+    }
+
+    pub fn hud_text(&self, caught: &[i32], target: u32) -> String {
+        let total: i32 = caught.iter().sum();
+        match self {
+            Objective::Sum => format!("SUM: {}/{}", total, target),
+            Objective::Product => {
+                let product: i64 = caught.iter().map(|n| *n as i64).product();
+                format!("PRODUCT: {}/{}", product, target)
+            }
+            Objective::ExactCount { count } => {
+                format!("SUM: {}/{} ({} left)", total, target, count.saturating_sub(caught.len()))
+            }
+            Objective::Parity { parity } => format!("{} SUM: {}/{}", parity.name().to_uppercase(), total, target),
+            Objective::NoRepeats => format!("UNIQUE SUM: {}/{}", total, target),
+            Objective::Countdown => format!("LEFT: {}", target as i32 - total),
+        }
+    }
+
+    /// One line explaining the objective, shown before the round starts.
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::Sum => "Add up to the target".to_string(),
+            Objective::Product => "Multiply up to the target".to_string(),
+            Objective::ExactCount { count } => format!("Add up to the target with exactly {} bubbles", count),
+            Objective::Parity { parity } => format!("Add up to the target using only {} numbers", parity.name()),
+            Objective::NoRepeats => "Add up to the target, no number twice".to_string(),
+            Objective::Countdown => "Take your catches away to land on exactly zero".to_string(),
+        }
+    }
+
+    /// Whether a round with this target can be won at all.
+    pub fn is_achievable(&self, target: u32) -> bool {
+        match self {
+            Objective::Sum | Objective::NoRepeats | Objective::Countdown => target >= 1,
+            Objective::Product => target >= 2,
+            Objective::ExactCount { count } => *count >= 1 && target as usize >= *count,
+            Objective::Parity { parity: Parity::Even } => target >= 2 && target.is_multiple_of(2),
+            Objective::Parity { parity: Parity::Odd } => target >= 1,
+            Objective::Parity { parity: Parity::Prime } => target >= 2,
+        }
+    }
+
+    /// Whether negative bubbles make sense for this objective, i.e. it only cares about the running total.
+    pub fn allows_negatives(&self) -> bool {
+        matches!(self, Objective::Sum | Objective::Countdown)
+    }
+
+    /// Whether a total over the target can still be brought back with negative bubbles. After
+    /// the catch that went over, there are a few more catches to do it in.
+    pub fn can_still_recover(&self, caught: &[i32], target: u32) -> bool {
+        if !self.allows_negatives() {
+            return false;
+        }
+        let mut total = 0;
+        // The catch that took the total over, since it was last back in range:
+        let mut went_over = None;
+        for (index, number) in caught.iter().enumerate() {
+            total += number;
+            if total > target as i32 {
+                went_over.get_or_insert(index);
+            } else {
+                went_over = None;
+            }
+        }
+        went_over.is_some_and(|index| caught.len() - 1 - index < constants::NEGATIVE_RECOVERY_CATCHES)
+    }
+
+    /// Turns a randomly drawn target into one that suits the objective.
+    pub fn make_target(&self, target: u32, rng: &mut impl Rng) -> u32 {
+        match self {
+            // Products grow fast, so build the target out of a few small factors instead:
+            Objective::Product => (0..rng.gen_range(2..=3)).map(|_| rng.gen_range(2..=9)).product(),
+            Objective::Parity { parity: Parity::Even } => (target.max(2) / 2) * 2,
+            Objective::ExactCount { count } => target.max(*count as u32),
+            Objective::Countdown => target.max(1),
+            _ => target.max(2),
+        }
+    }
+
+    /// Picks a bubble number that keeps the round winnable, or None to fall back to
+    /// the usual sum-based numbers. Some of the time it deliberately offers a number
+    /// that breaks the objective, so there's something to avoid.
+    pub fn pick_number(&self, caught: &[i32], target: u32, rng: &mut impl Rng) -> Option<i32> {
+        if *self == Objective::Sum {
+            return None;
+        }
+        let total: i32 = caught.iter().sum();
+        let target = target as i32;
+        let shortfall = (target - total).max(1);
+        let helpful = rng.gen_bool(0.7);
+        match *self {
+            Objective::Sum => None,
+            Objective::Product => {
+                let product: i32 = caught.iter().product::<i32>().max(1);
+                let remaining = (target / product).max(1);
+                let divisors: Vec<i32> = (2..=remaining).filter(|d| remaining % d == 0).collect();
+                if helpful && !divisors.is_empty() {
+                    Some(divisors[rng.gen_range(0..divisors.len())])
+                } else {
+                    Some(rng.gen_range(2..=9))
+                }
+            }
+            Objective::ExactCount { count } => {
+                let slots_left = count.saturating_sub(caught.len()).max(1) as i32;
+                if helpful && slots_left == 1 {
+                    return Some(shortfall);
+                }
+                // Leave at least 1 for each of the other slots:
+                let upper = (shortfall - (slots_left - 1)).max(1);
+                Some(rng.gen_range(1..=upper))
+            }
+            Objective::Parity { parity } => {
+                // Helpful numbers never leave a total that can't be finished:
+                let allowed: Vec<i32> =
+                    (1..=shortfall).filter(|n| parity.allows(*n) && parity.can_make(shortfall - n)).collect();
+                if helpful && !allowed.is_empty() {
+                    Some(allowed[rng.gen_range(0..allowed.len())])
+                } else {
+                    Some(rng.gen_range(1..=shortfall.max(2)))
+                }
+            }
+            Objective::NoRepeats => {
+                let fresh: Vec<i32> = (1..=shortfall).filter(|n| !caught.contains(n)).collect();
+                if helpful && !fresh.is_empty() {
+                    Some(fresh[rng.gen_range(0..fresh.len())])
+                } else {
+                    caught.last().copied().or(Some(rng.gen_range(1..=shortfall)))
+                }
+            }
+            Objective::Countdown => {
+                // Mostly numbers that fit in what's left, so younger players aren't left waiting:
+                let upper = if helpful { shortfall } else { target.max(1) };
+                Some(rng.gen_range(1..=upper))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMES: Objective = Objective::Parity { parity: Parity::Prime };
+
+    #[test]
+    fn prime_sums_fail_when_only_1_is_left() {
+        assert_eq!(PRIMES.evaluate(&[5, 3], 10), Progress::Incomplete);
+        assert!(matches!(PRIMES.evaluate(&[5, 3], 9), Progress::Failed(_)));
+        assert_eq!(PRIMES.evaluate(&[5, 3], 8), Progress::Complete);
+    }
+
+    #[test]
+    fn overshoots_not_brought_back_in_time_still_fail() {
+        assert!(Objective::Sum.can_still_recover(&[12], 10));
+        assert!(Objective::Sum.can_still_recover(&[12, -1], 10));
+        // Back in range, then over again, starts afresh:
+        assert!(Objective::Sum.can_still_recover(&[12, -3, 4], 10));
+        assert!(!Objective::Product.can_still_recover(&[12], 10));
+
+        let mut caught = vec![15];
+        caught.extend(std::iter::repeat_n(-1, constants::NEGATIVE_RECOVERY_CATCHES));
+        assert!(matches!(Objective::Sum.evaluate(&caught, 10), Progress::Failed(_)));
+        assert!(!Objective::Sum.can_still_recover(&caught, 10));
+        assert!(!Objective::Countdown.can_still_recover(&caught, 10));
+    }
+}
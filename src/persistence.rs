@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::constants;
+
+pub fn save_path(file_name: &str) -> PathBuf {
+    Path::new(constants::SAVE_DIR).join(file_name)
+}
+
+/// Reads a TOML save file, falling back to the default if it's missing or unreadable
+/// so a corrupt save never stops the game from starting.
+pub fn load_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+    match fs::read_to_string(path) {
+        Ok(contents) => toml::from_str(&contents).unwrap_or_else(|e| {
+            eprintln!("Ignoring unreadable save file {}: {}", path.display(), e);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, contents).map_err(|e| e.to_string())
+}
+
+pub fn save<T: Serialize>(path: &Path, value: &T) {
+    let result = toml::to_string(value)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| write_file(path, &contents));
+    if let Err(e) = result {
+        eprintln!("Could not save {}: {}", path.display(), e);
+    }
+}
+
+/// Writes out a report for reading outside the game. Returns whether it worked.
+pub fn export(path: &Path, contents: &str) -> bool {
+    match write_file(path, contents) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Could not export {}: {}", path.display(), e);
+            false
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+/// Keeps the spawn stream fair: any `window` bubbles in a row, counted from when the
+/// shortfall last changed, always include some combination that adds up to the shortfall.
+/// The spawner picks numbers as it likes and runs each one past `plan`, which only steps
+/// in when the window is about to close without a way to finish the round. Bubbles that
+/// don't count towards the total, like mines, go past `allow_other` so they take up their
+/// place in the window too.
+#[derive(Debug, Clone)]
+pub struct SpawnPlanner {
+    window: usize,
+    shortfall: i32,
+    recent: VecDeque<i32>,
+}
+
+/// Every total that can be made from some of the numbers, up to and including `limit`.
+fn reachable_totals(numbers: impl Iterator<Item = i32>, limit: i32) -> Vec<bool> {
+    let limit = limit.max(0) as usize;
+    let mut reachable = vec![false; limit + 1];
+    reachable[0] = true;
+    for n in numbers.filter(|n| *n > 0).map(|n| n as usize) {
+        for total in (n..=limit).rev() {
+            if reachable[total - n] {
+                reachable[total] = true;
+            }
+        }
+    }
+    reachable
+}
+
+/// Whether some of the numbers add up to exactly `shortfall`.
+pub fn can_complete(numbers: &[i32], shortfall: i32) -> bool {
+    shortfall > 0 && reachable_totals(numbers.iter().copied(), shortfall)[shortfall as usize]
+}
+
+impl SpawnPlanner {
+    /// A window of 0 switches planning off.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            shortfall: 0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Takes the number the spawner wants to use next and returns the one to actually spawn.
+    pub fn plan(&mut self, candidate: i32, shortfall: i32) -> i32 {
+        if !self.track(shortfall) {
+            return candidate;
+        }
+
+        let mut number = candidate;
+        let earlier = self.recent.iter().copied();
+        if self.closes_window() && !can_complete(&earlier.clone().chain([candidate]).collect::<Vec<_>>(), shortfall) {
+            // Top up the biggest total the earlier bubbles can make to exactly the shortfall:
+            let reachable = reachable_totals(earlier, shortfall - 1);
+            let best = (0..shortfall).rev().find(|t| reachable[*t as usize]).unwrap_or(0);
+            number = shortfall - best;
+        }
+        self.recent.push_back(number);
+        number
+    }
+
+    /// Whether the next bubble can be one that doesn't count towards the total. It can't
+    /// when the window is about to close and still needs a number to finish the round.
+    pub fn allow_other(&mut self, shortfall: i32) -> bool {
+        if !self.track(shortfall) {
+            return true;
+        }
+        if self.closes_window() && !can_complete(&self.recent.iter().copied().collect::<Vec<_>>(), shortfall) {
+            return false;
+        }
+        self.recent.push_back(0);
+        true
+    }
+
+    /// Gets ready to add the next bubble to the window, or returns false when planning is
+    /// off or the total is already past the target.
+    fn track(&mut self, shortfall: i32) -> bool {
+        if self.window == 0 || shortfall <= 0 {
+            return false;
+        }
+        if shortfall != self.shortfall {
+            // Something was caught or released, so what's on the way no longer matters:
+            self.shortfall = shortfall;
+            self.recent.clear();
+        }
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        true
+    }
+
+    /// Whether the next bubble is the last one of the window.
+    fn closes_window(&self) -> bool {
+        self.recent.len() + 1 == self.window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+    use rand_chacha::ChaCha8Rng;
+
+    fn every_window_completes(spawned: &[i32], window: usize, shortfall: i32) -> bool {
+        spawned.windows(window).all(|w| can_complete(w, shortfall))
+    }
+
+    #[test]
+    fn can_complete_finds_combinations() {
+        assert!(can_complete(&[4, 9, 2], 6));
+        assert!(can_complete(&[4, 9, 2], 15));
+        assert!(!can_complete(&[4, 9, 2], 8));
+        assert!(!can_complete(&[-3, 9], 6));
+        assert!(!can_complete(&[], 0));
+    }
+
+    #[test]
+    fn leaves_the_stream_alone_when_it_already_completes() {
+        let mut planner = SpawnPlanner::new(3);
+        assert_eq!(planner.plan(5, 10), 5);
+        assert_eq!(planner.plan(9, 10), 9);
+        assert_eq!(planner.plan(5, 10), 5);
+        assert_eq!(planner.plan(5, 10), 5);
+    }
+
+    #[test]
+    fn does_nothing_when_off_or_overshot() {
+        let mut off = SpawnPlanner::new(0);
+        assert_eq!(off.plan(50, 3), 50);
+        let mut overshot = SpawnPlanner::new(1);
+        assert_eq!(overshot.plan(50, -3), 50);
+    }
+
+    #[test]
+    fn every_window_is_solvable_over_many_seeds() {
+        for seed in 0..500 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let window = rng.gen_range(1..=10);
+            let shortfall = rng.gen_range(1..=150);
+            let mut planner = SpawnPlanner::new(window);
+            let spawned: Vec<i32> = (0..100)
+                .map(|_| {
+                    let candidate = rng.gen_range(1..=shortfall);
+                    planner.plan(candidate, shortfall)
+                })
+                .collect();
+            assert!(spawned.iter().all(|n| (1..=shortfall).contains(n)), "seed {}", seed);
+            assert!(every_window_completes(&spawned, window, shortfall), "seed {}: {:?}", seed, spawned);
+        }
+    }
+
+    #[test]
+    fn stays_solvable_as_the_player_catches() {
+        for seed in 0..500 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let window = rng.gen_range(2..=8);
+            let target = rng.gen_range(5..=75);
+            let mut planner = SpawnPlanner::new(window);
+            let mut total = 0;
+            let mut since_catch: Vec<i32> = vec![];
+            while total < target {
+                let shortfall = target - total;
+                // Candidates mostly miss, like the old purely random spawner:
+                let number = planner.plan(rng.gen_range(1..=target), shortfall);
+                since_catch.push(number);
+                assert!(
+                    since_catch.len() < window || every_window_completes(&since_catch, window, shortfall),
+                    "seed {}: {:?} can't make {}",
+                    seed,
+                    since_catch,
+                    shortfall
+                );
+                if number <= shortfall && rng.gen_bool(0.3) {
+                    total += number;
+                    since_catch.clear();
+                }
+            }
+            assert_eq!(total, target);
+        }
+    }
+
+    #[test]
+    fn mixes_in_negatives_without_counting_them() {
+        let mut planner = SpawnPlanner::new(2);
+        assert_eq!(planner.plan(-4, 7), -4);
+        assert_eq!(planner.plan(3, 7), 7);
+    }
+
+    #[test]
+    fn keeps_the_last_place_in_a_window_for_a_number() {
+        let mut planner = SpawnPlanner::new(3);
+        assert!(planner.allow_other(7));
+        assert_eq!(planner.plan(2, 7), 2);
+        assert!(!planner.allow_other(7));
+        assert_eq!(planner.plan(1, 7), 5);
+        assert!(planner.allow_other(7));
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::constants;
+use crate::curriculum::CurriculumProgress;
+use crate::difficulty::Difficulty;
+use crate::levels::Level;
+use crate::objectives::{self, Objective};
+use crate::rules;
+use crate::scoring::ScoreBreakdown;
+use crate::settings::Settings;
+use crate::structs::MinePenalty;
+
+/// What a won round gets scored on.
+pub struct WinInfo {
+    /// None when the round had no clock.
+    pub seconds_left: Option<u64>,
+    pub bubbles: usize,
+    /// Clean wins in a row, including this one.
+    pub streak: u32,
+    /// Overshoots during the round that didn't end it.
+    pub overshoots: u32,
+}
+
+/// How many points rounds are worth.
+pub trait ScoringPolicy: Debug {
+    fn score_win(&self, win: &WinInfo) -> ScoreBreakdown;
+    /// Taken off the score when an overshoot ends the round.
+    fn overshoot_penalty(&self) -> u32;
+}
+
+/// How many lives a game starts with and what losing one does.
+pub trait LifePolicy: Debug {
+    /// Whether the game has lives at all, and shows them on screen.
+    fn counts_lives(&self) -> bool;
+    fn starting_lives(&self) -> u8;
+    /// Most lives that can be held at once.
+    fn max_lives(&self) -> u8;
+    /// Points scored between extra lives, None if they can't be earned by scoring.
+    fn points_per_extra_life(&self) -> Option<u32>;
+    /// The lives left after losing a round.
+    fn lose_life(&self, lives: u8) -> u8;
+    fn is_out(&self, lives: u8) -> bool;
+
+    fn gain_life(&self, lives: u8) -> u8 {
+        lives.saturating_add(1).min(self.max_lives())
+    }
+
+    /// What a mine costs, given what the level says. Without lives, mines that would cost
+    /// one cost time instead, so they still matter.
+    fn mine_penalty(&self, level_penalty: MinePenalty) -> MinePenalty {
+        match level_penalty {
+            MinePenalty::Life if !self.counts_lives() => MinePenalty::Seconds(constants::MINE_TIME_PENALTY_SECONDS),
+            penalty => penalty,
+        }
+    }
+}
+
+/// How the rounds of a game follow on from each other.
+pub trait RoundProgressionPolicy: Debug {
+    /// Seconds on the clock for the round, given the previous round's.
+    fn round_seconds(&self, round: u32, previous_seconds: u64) -> u64;
+    /// Whether to stop on the win and next round screens, or go straight into the next target.
+    fn breaks_between_rounds(&self) -> bool;
+}
+
+/// What a target generator has to work with when setting up a round.
+pub struct RoundSetup<'a> {
+    pub round: u32,
+    pub round_seconds: u64,
+    pub difficulty: Difficulty,
+    /// The last few rounds' targets, most recent last.
+    pub recent_targets: &'a VecDeque<u32>,
+    pub rng: &'a mut ChaCha8Rng,
+    pub settings: &'a Settings,
+    pub curriculum: &'a CurriculumProgress,
+    pub campaign_levels: &'a [Level],
+}
+
+/// Picks each round's target and objective, and the level they're played on.
+pub trait TargetGenerator: Debug {
+    fn next_level(&self, setup: &mut RoundSetup) -> Level;
+}
+
+/// A point a round, the time bonus, bonuses for using few bubbles and a multiplier for clean streaks.
+#[derive(Debug)]
+pub struct StreakScoring {
+    pub time_bonus: bool,
+}
+
+impl ScoringPolicy for StreakScoring {
+    fn score_win(&self, win: &WinInfo) -> ScoreBreakdown {
+        let time_bonus = match (self.time_bonus, win.seconds_left) {
+            (true, Some(seconds)) => (seconds / constants::SECONDS_LEFT_PER_BONUS_POINT) as u32,
+            _ => 0,
+        };
+        ScoreBreakdown::for_win(time_bonus, win.bubbles, win.streak, win.overshoots)
+    }
+
+    fn overshoot_penalty(&self) -> u32 {
+        constants::OVERSHOOT_PENALTY_POINTS
+    }
+}
+
+/// One life lost per round lost, and the game ends on the last one. More can be earned
+/// by scoring, or from heart bubbles, up to `max`.
+#[derive(Debug)]
+pub struct LimitedLives {
+    pub lives: u8,
+    pub max: u8,
+    pub points_per_extra_life: Option<u32>,
+}
+
+impl LifePolicy for LimitedLives {
+    fn counts_lives(&self) -> bool {
+        true
+    }
+
+    fn starting_lives(&self) -> u8 {
+        self.lives
+    }
+
+    fn max_lives(&self) -> u8 {
+        self.max
+    }
+
+    fn points_per_extra_life(&self) -> Option<u32> {
+        self.points_per_extra_life
+    }
+
+    fn lose_life(&self, lives: u8) -> u8 {
+        lives.saturating_sub(1)
+    }
+
+    fn is_out(&self, lives: u8) -> bool {
+        lives == 0
+    }
+}
+
+/// Play carries on however many rounds are lost.
+#[derive(Debug)]
+pub struct NoLives;
+
+impl LifePolicy for NoLives {
+    fn counts_lives(&self) -> bool {
+        false
+    }
+
+    fn starting_lives(&self) -> u8 {
+        0
+    }
+
+    fn max_lives(&self) -> u8 {
+        0
+    }
+
+    fn points_per_extra_life(&self) -> Option<u32> {
+        None
+    }
+
+    fn lose_life(&self, lives: u8) -> u8 {
+        lives
+    }
+
+    fn is_out(&self, _lives: u8) -> bool {
+        false
+    }
+}
+
+/// Each round after the first has a little less time, down to a minimum.
+#[derive(Debug)]
+pub struct ShrinkingClock;
+
+impl RoundProgressionPolicy for ShrinkingClock {
+    fn round_seconds(&self, round: u32, previous_seconds: u64) -> u64 {
+        if previous_seconds > constants::MIN_ROUND_TIME_SECONDS && round > 1 {
+            previous_seconds - constants::TIME_DEDUCTED_PER_ROUND
+        } else {
+            previous_seconds
+        }
+    }
+
+    fn breaks_between_rounds(&self) -> bool {
+        true
+    }
+}
+
+/// Every round has the same time, or takes it from its level.
+#[derive(Debug)]
+pub struct SteadyClock {
+    pub breaks_between_rounds: bool,
+}
+
+impl RoundProgressionPolicy for SteadyClock {
+    fn round_seconds(&self, _round: u32, previous_seconds: u64) -> u64 {
+        previous_seconds
+    }
+
+    fn breaks_between_rounds(&self) -> bool {
+        self.breaks_between_rounds
+    }
+}
+
+/// How much a target is favoured: bigger ones in the range take more bubbles to make, and
+/// ones that aren't multiples of 5 take more working out.
+fn target_weight(target: u32, min: u32, max: u32) -> u32 {
+    let size = 1 + 2 * (target - min) / (max - min).max(1);
+    if target.is_multiple_of(5) {
+        size
+    } else {
+        size * 2
+    }
+}
+
+/// A target between `min` and `max` that none of the last few rounds had, unless the
+/// range is too small to avoid them.
+fn pick_target(setup: &mut RoundSetup, min: u32, max: u32, favour_interesting: bool) -> u32 {
+    let mut candidates: Vec<u32> = (min..=max).filter(|t| !setup.recent_targets.contains(t)).collect();
+    if candidates.is_empty() {
+        candidates = (min..=max).collect();
+    }
+    if favour_interesting {
+        candidates.choose_weighted(setup.rng, |t| target_weight(*t, min, max)).copied().unwrap_or(min)
+    } else {
+        candidates.choose(setup.rng).copied().unwrap_or(min)
+    }
+}
+
+/// Targets that grow with the round at the pace the difficulty sets, plain sums to begin
+/// with and other objectives mixed in on later rounds.
+#[derive(Debug)]
+pub struct EndlessTargets;
+
+impl TargetGenerator for EndlessTargets {
+    fn next_level(&self, setup: &mut RoundSetup) -> Level {
+        let mut objective = Objective::Sum;
+        if setup.round >= constants::OBJECTIVES_FROM_ROUND && setup.rng.gen_bool(constants::VARIANT_OBJECTIVE_PROBABILITY) {
+            objective = *objectives::VARIANT_OBJECTIVES.choose(setup.rng).unwrap();
+        }
+        let tuning = setup.difficulty.tuning();
+        let (min, max) = tuning.target_range(setup.round);
+        let target = pick_target(setup, min, max, tuning.favour_interesting_targets);
+        let target = objective.make_target(target, setup.rng);
+        Level::endless(setup.round, target, objective, setup.round_seconds)
+    }
+}
+
+/// Small targets counted down to zero. They grow with the round like endless targets,
+/// but stay between MIN_COUNTDOWN_TARGET and MAX_COUNTDOWN_TARGET.
+#[derive(Debug)]
+pub struct CountdownTargets;
+
+impl TargetGenerator for CountdownTargets {
+    fn next_level(&self, setup: &mut RoundSetup) -> Level {
+        let tuning = setup.difficulty.tuning();
+        let (min, max) = tuning.target_range(setup.round);
+        let max = max.clamp(constants::MIN_COUNTDOWN_TARGET, constants::MAX_COUNTDOWN_TARGET);
+        let min = min.clamp(constants::MIN_COUNTDOWN_TARGET, max);
+        let target = pick_target(setup, min, max, tuning.favour_interesting_targets);
+        Level::endless(setup.round, target, Objective::Countdown, setup.round_seconds)
+    }
+}
+
+/// Targets and bubble speed from the player's zen settings. The player picks the range,
+/// so it doesn't grow with the round or depend on the difficulty.
+#[derive(Debug)]
+pub struct ZenTargets;
+
+impl TargetGenerator for ZenTargets {
+    fn next_level(&self, setup: &mut RoundSetup) -> Level {
+        let (min_target, max_target) = rules::ZEN_TARGET_RANGES[setup.settings.zen_target_range];
+        let (_, min_speed, max_speed) = rules::ZEN_SPEEDS[setup.settings.zen_speed];
+        let target = pick_target(setup, min_target, max_target, false);
+        Level::zen(target, min_speed, max_speed)
+    }
+}
+
+/// Targets from the curriculum stage the player is on. The stage sets the range, and moving
+/// up a stage is what makes it harder, so it doesn't grow with the round or difficulty.
+#[derive(Debug)]
+pub struct CurriculumTargets;
+
+impl TargetGenerator for CurriculumTargets {
+    fn next_level(&self, setup: &mut RoundSetup) -> Level {
+        let stage = setup.curriculum.stage();
+        let target = pick_target(setup, stage.min_target, stage.max_target, false);
+        Level::curriculum(stage, target)
+    }
+}
+
+/// The same campaign level every round.
+#[derive(Debug)]
+pub struct CampaignLevel {
+    pub index: usize,
+}
+
+impl TargetGenerator for CampaignLevel {
+    fn next_level(&self, setup: &mut RoundSetup) -> Level {
+        setup.campaign_levels[self.index].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mines_cost_time_without_lives() {
+        let seconds = MinePenalty::Seconds(constants::MINE_TIME_PENALTY_SECONDS);
+        assert_eq!(NoLives.mine_penalty(MinePenalty::Life), seconds);
+        assert_eq!(NoLives.mine_penalty(MinePenalty::Seconds(2)), MinePenalty::Seconds(2));
+
+        let lives = LimitedLives { lives: 3, max: 6, points_per_extra_life: None };
+        assert_eq!(lives.mine_penalty(MinePenalty::Life), MinePenalty::Life);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants;
+use crate::persistence;
+
+pub const DEFAULT_PROFILE: &str = "Player";
+pub const MAX_NAME_LENGTH: usize = 16;
+
+/// Save files that belong to a player rather than the machine.
+const PLAYER_FILES: &[&str] = &[
+    constants::CAMPAIGN_SAVE_FILE,
+    constants::DAILY_SAVE_FILE,
+    constants::CURRICULUM_SAVE_FILE,
+    constants::FACTS_SAVE_FILE,
+    constants::SESSIONS_SAVE_FILE,
+    constants::SETTINGS_SAVE_FILE,
+    constants::HIGH_SCORES_SAVE_FILE,
+    constants::STATS_SAVE_FILE,
+    constants::ACHIEVEMENTS_SAVE_FILE,
+];
+
+/// Where a profile keeps its save files: saves/profiles/<name>/.
+pub fn profile_dir(name: &str) -> PathBuf {
+    persistence::save_path(constants::PROFILES_DIR).join(name)
+}
+
+pub fn profile_path(name: &str, file_name: &str) -> PathBuf {
+    profile_dir(name).join(file_name)
+}
+
+/// Trims the name and checks it can be used as a directory name and isn't taken.
+pub fn validate_name(name: &str, existing: &[String]) -> Result<String, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Type a name first".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!("Names can be up to {} letters", MAX_NAME_LENGTH));
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+        return Err("Only letters, numbers, spaces, - and _".to_string());
+    }
+    if existing.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+        return Err(format!("There's already a {}", name));
+    }
+    Ok(name)
+}
+
+/// The players on this machine and who's playing now.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileIndex {
+    pub current: String,
+    pub names: Vec<String>,
+}
+
+impl Default for ProfileIndex {
+    fn default() -> Self {
+        Self {
+            current: DEFAULT_PROFILE.to_string(),
+            names: vec![DEFAULT_PROFILE.to_string()],
+        }
+    }
+}
+
+impl ProfileIndex {
+    /// Loads the profile list. The first time round, saves from before there were profiles
+    /// are moved into the default profile so nobody loses their progress.
+    pub fn load() -> Self {
+        let path = persistence::save_path(constants::PROFILES_SAVE_FILE);
+        if path.exists() {
+            let mut index: ProfileIndex = persistence::load_or_default(&path);
+            if index.names.is_empty() {
+                index = ProfileIndex::default();
+            }
+            if !index.names.contains(&index.current) {
+                index.current = index.names[0].clone();
+            }
+            return index;
+        }
+
+        let index = ProfileIndex::default();
+        for file_name in PLAYER_FILES {
+            let old_path = persistence::save_path(file_name);
+            if old_path.exists() {
+                move_file(&old_path, &profile_path(DEFAULT_PROFILE, file_name));
+            }
+        }
+        index.save();
+        index
+    }
+
+    pub fn save(&self) {
+        persistence::save(&persistence::save_path(constants::PROFILES_SAVE_FILE), self);
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<String, String> {
+        let name = validate_name(name, &self.names)?;
+        self.names.push(name.clone());
+        self.save();
+        Ok(name)
+    }
+
+    pub fn rename(&mut self, old_name: &str, new_name: &str) -> Result<String, String> {
+        let others: Vec<String> = self.names.iter().filter(|n| *n != old_name).cloned().collect();
+        let new_name = validate_name(new_name, &others)?;
+        let old_dir = profile_dir(old_name);
+        if old_dir.exists() {
+            fs::rename(&old_dir, profile_dir(&new_name)).map_err(|e| format!("Couldn't rename: {}", e))?;
+        }
+        for name in self.names.iter_mut().filter(|n| *n == old_name) {
+            *name = new_name.clone();
+        }
+        if self.current == old_name {
+            self.current = new_name.clone();
+        }
+        self.save();
+        Ok(new_name)
+    }
+
+    /// Deletes the profile and all its saves. The last profile can't be deleted.
+    pub fn delete(&mut self, name: &str) -> Result<(), String> {
+        if self.names.len() <= 1 {
+            return Err("Can't delete the only profile".to_string());
+        }
+        let dir = profile_dir(name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(|e| format!("Couldn't delete: {}", e))?;
+        }
+        self.names.retain(|n| n != name);
+        if self.current == name {
+            self.current = self.names[0].clone();
+        }
+        self.save();
+        Ok(())
+    }
+}
+
+fn move_file(from: &Path, to: &Path) {
+    let result = to
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::rename(from, to));
+    if let Err(e) = result {
+        eprintln!("Could not move {} to {}: {}", from.display(), to.display(), e);
+    }
+}
+
+/// Best score per game mode, keyed by the mode's name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub best: BTreeMap<String, u32>,
+}
+
+impl HighScores {
+    pub fn best_for(&self, mode: &str) -> Option<u32> {
+        self.best.get(mode).copied()
+    }
+
+    /// Keeps the better of the new and previous scores. Returns true if it's a new best.
+    pub fn record(&mut self, mode: &str, score: u32) -> bool {
+        if self.best_for(mode).is_some_and(|best| best >= score) {
+            return false;
+        }
+        self.best.insert(mode.to_string(), score);
+        true
+    }
+}
@@ -1,5 +1,13 @@
+use crate::achievements;
+use crate::campaign;
 use crate::constants;
-use crate::structs::{GameMode, GameState};
+use crate::curriculum;
+use crate::daily;
+use crate::profiles;
+use crate::rules::{self, Clock};
+use crate::session;
+use crate::settings::{self, key_name};
+use crate::structs::{BubbleKind, GameMode, GameState, PlayMode, ProfileEdit};
 use ggez::glam::Vec2;
 use ggez::graphics::{self, Canvas, Color, DrawParam, PxScale, Text, TextFragment};
 use ggez::mint::Point2;
@@ -10,6 +18,14 @@ pub fn render(game: &GameState, ctx: &mut Context) -> GameResult {
 
     match game.current_mode {
         GameMode::IntroScreen => draw_intro_screen(ctx, &mut canvas, game),
+        GameMode::StatsScreen => draw_stats_screen(ctx, &mut canvas, game),
+        GameMode::AchievementsScreen => draw_achievements_screen(ctx, &mut canvas, game),
+        GameMode::ProfilesScreen => draw_profiles_screen(ctx, &mut canvas, game),
+        GameMode::KeyBindingsScreen => draw_key_bindings_screen(ctx, &mut canvas, game),
+        GameMode::LevelSelectScreen => draw_level_select_screen(ctx, &mut canvas, game),
+        GameMode::DailyScreen => draw_daily_screen(ctx, &mut canvas, game),
+        GameMode::ZenSetupScreen => draw_zen_setup_screen(ctx, &mut canvas, game),
+        GameMode::TimeUpScreen => draw_time_up_screen(ctx, &mut canvas, game),
         GameMode::OvershotScreen => draw_overshot_screen(ctx, &mut canvas, game),
         GameMode::OutOfTimeScreen => draw_out_of_time_screen(ctx, &mut canvas, game),
         GameMode::WinScreen => draw_win_screen(ctx, &mut canvas, game),
@@ -17,21 +33,29 @@ pub fn render(game: &GameState, ctx: &mut Context) -> GameResult {
         GameMode::NextRoundScreen => draw_next_round_screen(ctx, &mut canvas, game),
         GameMode::Running => {
            // draw_target_number(&mut canvas, game.current_target);
+            draw_walls(ctx, &mut canvas, game);
             draw_ship(ctx, &mut canvas, game);
             draw_bubbles(ctx, &mut canvas, game);
             draw_current_total(ctx, game, &mut canvas);
             draw_score(game, &mut canvas);
-            draw_lives(ctx, &mut canvas, game);
-            draw_remaining_time(ctx, &mut canvas, game);
+            if game.rules.lives.counts_lives() {
+                draw_lives(ctx, &mut canvas, game);
+            }
+            if game.rules.clock != Clock::Off {
+                draw_remaining_time(ctx, &mut canvas, game);
+            }
+            draw_caught_numbers(ctx, &mut canvas, game);
+            draw_feedback(ctx, &mut canvas, game);
         }
     }
-    return canvas.finish(ctx);
+    draw_achievement_toast(ctx, &mut canvas, game);
+    canvas.finish(ctx)
 }
 
 fn draw_current_total(_ctx: &Context, game: &GameState, canvas: &mut Canvas) {
     // Text:
     let text = Text::new(TextFragment {
-        text: format!("SUM: {}/{}", game.compute_caught_sum(), game.current_target),
+        text: game.level.objective.hud_text(&game.numbers_caught, game.current_target),
         color: Some(Color::new(1.0, 1.0, 1.0, 1.0)),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(65.0)),
@@ -44,12 +68,11 @@ fn draw_current_total(_ctx: &Context, game: &GameState, canvas: &mut Canvas) {
 
 fn draw_remaining_time(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
     // Text:
-    let color: Color;
-    match game.round_time_remaining_seconds {
-        0..=5 => color = Color::RED,
-        6..=10 => color = Color::YELLOW,
-        _ => color = Color::BLUE,
-    }
+    let color: Color = match game.round_time_remaining_seconds {
+        0..=5 => Color::RED,
+        6..=10 => Color::YELLOW,
+        _ => Color::BLUE,
+    };
     let text = Text::new(TextFragment {
         text: format!("TIME: {}/{}", game.round_time_remaining_seconds, game.round_allowed_time_seconds),
         color: Some(color),
@@ -64,8 +87,13 @@ fn draw_remaining_time(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
 }
 
 fn draw_score(game: &GameState, canvas: &mut Canvas) {
+    // Time attack is played for targets cleared, not points:
+    let score = match game.play_mode {
+        PlayMode::TimeAttack => format!("Cleared: {}", game.targets_hit),
+        _ => format!("Score: {}", game.score),
+    };
     let text = Text::new(TextFragment {
-        text: format!("Score: {}", game.score),
+        text: score,
         color: Some(Color::new(0.0, 0.0, 1.0, 1.0)),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(30.0)),
@@ -98,7 +126,7 @@ fn draw_ship(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
 
 fn draw_lives(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
     let text = Text::new(TextFragment {
-        text: format!("Lives: "),
+        text: "Lives: ".to_string(),
         color: Some(Color::new(0.0, 1.0, 0.0, 1.0)),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(30.0)),
@@ -109,12 +137,22 @@ fn draw_lives(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
         DrawParam::from(Vec2::new(10.0, 80.0)),
     );
 
-    for i in 1..=game.lives_remaining {
+    // A dot per life, filled for the ones left, spaced to fit however many can be held.
+    // Past a point that's too many dots to count, so it's one dot and a number instead:
+    let max_lives = game.rules.lives.max_lives().max(game.lives_remaining);
+    let (dots, count_text) = if max_lives <= constants::MAX_LIFE_DOTS {
+        (max_lives, None)
+    } else {
+        (1, Some(format!("x {}", game.lives_remaining)))
+    };
+    let spacing = (constants::LIVES_HUD_WIDTH / dots.max(1) as f32).min(60.0);
+    for i in 1..=dots {
+        let filled = count_text.is_some() || i <= game.lives_remaining;
         let dot_mesh = graphics::Mesh::new_circle(
             ctx,
-            graphics::DrawMode::fill(),
+            if filled { graphics::DrawMode::fill() } else { graphics::DrawMode::stroke(1.0) },
             Point2::from(Vec2::new(
-                70.0 + (60.0 * i as f32),
+                130.0 + (spacing * (i - 1) as f32),
                 100.0,
             )),
             5.0,
@@ -125,14 +163,162 @@ fn draw_lives(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
 
         canvas.draw(&dot_mesh, DrawParam::default());
     }
+    if let Some(count_text) = count_text {
+        let text = Text::new(TextFragment {
+            text: count_text,
+            color: Some(Color::GREEN),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(30.0)),
+        });
+        canvas.draw(&text, DrawParam::from(Vec2::new(145.0, 80.0)));
+    }
+}
+
+fn draw_caught_numbers(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let caught_text = Text::new(TextFragment {
+        text: format!("Caught: {}", game.level.objective.working_text(&game.numbers_caught, game.current_target)),
+        color: Some(Color::WHITE),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(30.0)),
+    });
+    canvas.draw(&caught_text, DrawParam::from(Vec2::new(10.0, 130.0)));
+
+    if let (true, Some(last)) = (game.level.release_allowed, game.numbers_caught.last()) {
+        let now = ctx.time.time_since_start().as_secs();
+        let cooldown = game.release_cooldown_remaining(now);
+        let release_text = Text::new(TextFragment {
+            text: if let Some(until) = game.overshoot_grace_until_seconds {
+                format!("Over! [{}] release {} within {}s", key_name(game.settings.keys.release), last, until.saturating_sub(now))
+            } else if cooldown == 0 {
+                format!("[{}] release {} (-{}s)", key_name(game.settings.keys.release), last, constants::RELEASE_TIME_PENALTY_SECONDS)
+            } else {
+                format!("Release ready in {}s", cooldown)
+            },
+            color: Some(match (game.overshoot_grace_until_seconds, cooldown) {
+                (Some(_), _) => Color::RED,
+                (None, 0) => Color::YELLOW,
+                (None, _) => Color::new(0.5, 0.5, 0.5, 1.0),
+            }),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(20.0)),
+        });
+        canvas.draw(&release_text, DrawParam::from(Vec2::new(10.0, 165.0)));
+    }
+
+    let hints_left = constants::HINTS_PER_ROUND - game.hints_used;
+    let hint_text = Text::new(TextFragment {
+        text: if hints_left > 0 {
+            format!("[{}] hint (-{} point, {} left)", key_name(game.settings.keys.hint), constants::HINT_SCORE_COST, hints_left)
+        } else {
+            "No hints left this round".to_string()
+        },
+        color: Some(if hints_left > 0 { Color::YELLOW } else { Color::new(0.5, 0.5, 0.5, 1.0) }),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(20.0)),
+    });
+    canvas.draw(&hint_text, DrawParam::from(Vec2::new(10.0, 190.0)));
+}
+
+fn draw_feedback(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let Some(message) = &game.feedback_message else {
+        return;
+    };
+    if ctx.time.time_since_start().as_secs() >= game.feedback_until_seconds {
+        return;
+    }
+    let feedback_text = Text::new(TextFragment {
+        text: message.clone(),
+        color: Some(Color::YELLOW),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(40.0)),
+    });
+    canvas.draw(
+        &feedback_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 2.0,
+        )),
+    );
+}
+
+fn draw_achievement_toast(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let (Some(name), Some(_)) = (game.achievement_toasts.front(), game.toast_until_seconds) else {
+        return;
+    };
+    let toast_text = Text::new(TextFragment {
+        text: format!("Achievement unlocked: {}", name),
+        color: Some(Color::from_rgb(255, 215, 0)),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(28.0)),
+    });
+    canvas.draw(
+        &toast_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 9.0,
+            game.window_size.height as f32 - 50.0,
+        )),
+    );
+}
+
+fn draw_walls(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    for wall in game.walls.iter() {
+        let wall_mesh = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(wall.position.x, wall.position.y, wall.size.x, wall.size.y),
+            Color::from_rgb(120, 120, 120),
+        )
+        .expect("error creating wall mesh");
+
+        canvas.draw(&wall_mesh, DrawParam::default());
+    }
 }
 
 fn draw_bubbles(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
-    let color: Color = Color::from_rgb(71, 252, 222);
+    let hinted = game.hinted_bubbles(ctx.time.time_since_start().as_secs());
     for bubble in game.bubbles.iter() {
+        if hinted.contains(&bubble.index) {
+            let glow_mesh = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::fill(),
+                Point2::from(bubble.position),
+                constants::BUBBLE_RADIUS + 8.0,
+                1.0,
+                Color::new(1.0, 0.9, 0.2, 0.35),
+            )
+            .expect("error creating hint mesh");
+            canvas.draw(&glow_mesh, DrawParam::default());
+        }
+        let (color, draw_mode, label) = match bubble.kind {
+            BubbleKind::Number if bubble.number < 0 => (
+                Color::from_rgb(255, 150, 50),
+                graphics::DrawMode::stroke(4.0),
+                format!("{}", bubble.number),
+            ),
+            BubbleKind::Number => (
+                Color::from_rgb(71, 252, 222),
+                graphics::DrawMode::stroke(2.0),
+                bubble.label.clone().unwrap_or_else(|| format!("{}", bubble.number)),
+            ),
+            BubbleKind::Decoy => (
+                Color::from_rgb(140, 140, 140),
+                graphics::DrawMode::stroke(1.0),
+                format!("{}", bubble.number),
+            ),
+            BubbleKind::Mine => (
+                Color::RED,
+                graphics::DrawMode::fill(),
+                "*".to_string(),
+            ),
+            BubbleKind::Heart => (
+                Color::from_rgb(255, 105, 180),
+                graphics::DrawMode::stroke(3.0),
+                "♥".to_string(),
+            ),
+        };
         let bubble_mesh = graphics::Mesh::new_circle(
             ctx,
-            graphics::DrawMode::stroke(2.0),
+            draw_mode,
             Point2::from(bubble.position),
             constants::BUBBLE_RADIUS,
             1.0,
@@ -142,16 +328,23 @@ fn draw_bubbles(ctx: &Context, canvas: &mut Canvas, game: &GameState) {
 
         canvas.draw(&bubble_mesh, DrawParam::default());
 
-        let text = Text::new(TextFragment {
-            text: format!("{}", bubble.number),
-            color: Some(color),
+        let mut text = Text::new(TextFragment {
+            text: label,
+            color: Some(if bubble.kind == BubbleKind::Mine { Color::BLACK } else { color }),
             font: Some("LiberationMono-Regular".into()),
             scale: Some(PxScale::from(30.0)),
         });
 
+        // Centre the label on the bubble, shrinking long ones like "12×12" to fit inside it:
+        let max_width = constants::BUBBLE_RADIUS * 2.0 - 6.0;
+        let mut size = text.measure(ctx).map(Vec2::from).unwrap_or(Vec2::new(24.0, 20.0));
+        if size.x > max_width {
+            text.set_scale(PxScale::from(30.0 * max_width / size.x));
+            size = text.measure(ctx).map(Vec2::from).unwrap_or(size);
+        }
         canvas.draw(
             &text,
-            DrawParam::from(bubble.position - Vec2::new(12.0, 10.0)),
+            DrawParam::from(bubble.position - size / 2.0),
         );
     }
 }
@@ -164,13 +357,28 @@ fn draw_overshot_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
         scale: Some(PxScale::from(100.0)),
     });
 
+    let reason_text = Text::new(TextFragment {
+        text: format!("{}  (-{} point)", game.overshoot_reason, game.rules.scoring.overshoot_penalty()),
+        color: Some(Color::WHITE),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(30.0)),
+    });
+
     let press_space_text = Text::new(TextFragment {
-        text: format!("Press space to continue..."),
+        text: "Press space to continue...".to_string(),
         color: Some(Color::RED),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(50.0)),
     });
 
+    canvas.draw(
+        &reason_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0 - 60.0,
+        )),
+    );
+
     canvas.draw(
         &overshot_text,
         DrawParam::from(Vec2::new(
@@ -197,7 +405,7 @@ fn draw_out_of_time_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState
     });
 
     let press_space_text = Text::new(TextFragment {
-        text: format!("Press space to continue..."),
+        text: "Press space to continue...".to_string(),
         color: Some(Color::RED),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(50.0)),
@@ -228,8 +436,26 @@ fn draw_death_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
         scale: Some(PxScale::from(100.0)),
     });
 
+    let mut notes: Vec<String> = vec![];
+    match game.play_mode {
+        PlayMode::Daily { scored: true } => notes.push("Saved as today's score".to_string()),
+        PlayMode::Daily { scored: false } => notes.push("Practice run, not scored".to_string()),
+        _ => (),
+    }
+    if game.new_best_score {
+        notes.push("New best!".to_string());
+    } else if let Some(best) = game.high_scores.best_for(session::mode_name(game.play_mode)) {
+        notes.push(format!("Your best: {}", best));
+    }
+    let notes_text = Text::new(TextFragment {
+        text: notes.join("\n"),
+        color: Some(Color::RED),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(40.0)),
+    });
+
     let press_space_text = Text::new(TextFragment {
-        text: format!("Press space to play again..."),
+        text: "Press space to play again...".to_string(),
         color: Some(Color::RED),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(50.0)),
@@ -244,12 +470,20 @@ fn draw_death_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
     );
 
     canvas.draw(
-        &press_space_text,
+        &notes_text,
         DrawParam::from(Vec2::new(
             game.window_size.width as f32 / 7.0,
             game.window_size.height as f32 / 3.0 + 200.0,
         )),
     );
+
+    canvas.draw(
+        &press_space_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0 + 300.0,
+        )),
+    );
 }
 
 fn draw_next_round_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
@@ -261,7 +495,7 @@ fn draw_next_round_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState)
     });
 
     let press_space_text = Text::new(TextFragment {
-        text: format!("Press space to continue..."),
+        text: "Press space to continue...".to_string(),
         color: Some(Color::GREEN),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(50.0)),
@@ -282,18 +516,106 @@ fn draw_next_round_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState)
             game.window_size.height as f32 / 3.0 + 100.0,
         )),
     );
+
+    let objective_text = Text::new(TextFragment {
+        text: game.level.objective.describe(),
+        color: Some(Color::YELLOW),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(30.0)),
+    });
+
+    canvas.draw(
+        &objective_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0 + 170.0,
+        )),
+    );
+
+    if game.campaign_level_index().is_some() || game.play_mode == PlayMode::Curriculum {
+        let level_text = Text::new(TextFragment {
+            text: format!("{}\n{}", game.level.name, game.level.description),
+            color: Some(Color::WHITE),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(30.0)),
+        });
+
+        canvas.draw(
+            &level_text,
+            DrawParam::from(Vec2::new(
+                game.window_size.width as f32 / 7.0,
+                game.window_size.height as f32 / 3.0 - 100.0,
+            )),
+        );
+    }
+}
+
+fn star_string(stars: u8) -> String {
+    let earned = "*".repeat(stars as usize);
+    let missing = "-".repeat(campaign::MAX_STARS.saturating_sub(stars) as usize);
+    format!("[{}{}]", earned, missing)
 }
 
 fn draw_win_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    if game.campaign_level_index().is_some() {
+        let stars_text = Text::new(TextFragment {
+            text: format!("{} {}", game.level.name, star_string(game.last_round_stars)),
+            color: Some(Color::YELLOW),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(50.0)),
+        });
+
+        canvas.draw(
+            &stars_text,
+            DrawParam::from(Vec2::new(
+                game.window_size.width as f32 / 7.0,
+                game.window_size.height as f32 / 3.0 - 100.0,
+            )),
+        );
+    }
+
+    if game.play_mode == PlayMode::Curriculum {
+        let stage_text = Text::new(TextFragment {
+            text: format!(
+                "{} ({}/{} clean rounds)",
+                game.curriculum_progress.stage().name,
+                game.curriculum_progress.streak,
+                curriculum::ROUNDS_TO_MASTER
+            ),
+            color: Some(Color::YELLOW),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(40.0)),
+        });
+
+        canvas.draw(
+            &stage_text,
+            DrawParam::from(Vec2::new(
+                game.window_size.width as f32 / 7.0,
+                game.window_size.height as f32 / 3.0 - 100.0,
+            )),
+        );
+    }
+
     let new_target_text = Text::new(TextFragment {
-        text: format!("NOICE!\nNew score: {}\n(Time bonus: {})", game.score, game.round_time_bonus),
+        text: format!("NOICE!\nNew score: {}", game.score),
         color: Some(Color::GREEN),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(100.0)),
     });
 
+    let mut breakdown = game.score_breakdown.lines();
+    if game.extra_life_earned {
+        breakdown.push(format!("Extra life! {} lives now", game.lives_remaining));
+    }
+    let breakdown_text = Text::new(TextFragment {
+        text: breakdown.join("\n"),
+        color: Some(Color::WHITE),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(28.0)),
+    });
+
     let press_space_text = Text::new(TextFragment {
-        text: format!("Press space to continue..."),
+        text: "Press space to continue...".to_string(),
         color: Some(Color::GREEN),
         font: Some("LiberationMono-Regular".into()),
         scale: Some(PxScale::from(50.0)),
@@ -307,17 +629,25 @@ fn draw_win_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
         )),
     );
 
+    canvas.draw(
+        &breakdown_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0 + 210.0,
+        )),
+    );
+
     canvas.draw(
         &press_space_text,
         DrawParam::from(Vec2::new(
             game.window_size.width as f32 / 7.0,
-            game.window_size.height as f32 / 3.0 + 300.0,
+            game.window_size.height as f32 / 3.0 + 440.0,
         )),
     );
 }
 
 fn draw_intro_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
-    let description = format!("Catch bubbles in each round to make up the target.\nBubbles get faster with each round.\nTime bonus of 1 point for every {} seconds left.\nOvershooting costs a life!\n\nPress space to start...", constants::SECONDS_LEFT_PER_BONUS_POINT);
+    let description = format!("Catch bubbles in each round to make up the target.\nBubbles get faster with each round.\nTime bonus of 1 point for every {} seconds left.\nOvershooting costs a life and a point!\nPress {} to release your last catch, even an overshoot (-{}s).\nAvoid the red mines, grey zeroes are decoys.\n\nPress space to start...\nPress L for the campaign, D for the daily challenge,\nZ for zen practice, T for time attack, C for countdown,\nM for maths practice, E to export a progress report,\nS for your stats, A for achievements.\nN toggles negative bubbles: {}", constants::SECONDS_LEFT_PER_BONUS_POINT, key_name(game.settings.keys.release), constants::RELEASE_TIME_PENALTY_SECONDS, if game.settings.negative_bubbles { "on" } else { "off" });
 
     let desc_text = Text::new(TextFragment {
         text: description,
@@ -333,4 +663,430 @@ fn draw_intro_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
             game.window_size.height as f32 / 3.0,
         )),
     );
-}
\ No newline at end of file
+    let profile_text = Text::new(TextFragment {
+        text: format!(
+            "Playing as {}  (P for profiles)\nDifficulty: {}  (Tab)   Ship: {}  (I)",
+            game.profiles.current,
+            game.settings.difficulty.name(),
+            if game.settings.arcade_movement { "Arcade" } else { "Smooth" }
+        ),
+        color: Some(Color::WHITE),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(25.0)),
+    });
+    canvas.draw(&profile_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 80.0)));
+
+    if let Some(message) = &game.intro_message {
+        let message_text = Text::new(TextFragment {
+            text: message.clone(),
+            color: Some(Color::YELLOW),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(25.0)),
+        });
+        canvas.draw(&message_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 40.0)));
+    }
+}
+fn draw_profiles_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let title_text = Text::new(TextFragment {
+        text: "PROFILES".to_string(),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(50.0)),
+    });
+    canvas.draw(&title_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 40.0)));
+
+    let mut lines: Vec<String> = game
+        .profiles
+        .names
+        .iter()
+        .map(|name| {
+            let playing = if *name == game.profiles.current { "  (playing)" } else { "" };
+            format!("{}{}", name, playing)
+        })
+        .collect();
+    lines.push("+ New profile".to_string());
+    for (index, line) in lines.iter().enumerate() {
+        let selected = index == game.profile_cursor;
+        let line_text = Text::new(TextFragment {
+            text: format!("{} {}", if selected { ">" } else { " " }, line),
+            color: Some(if selected { Color::YELLOW } else { Color::from_rgb(71, 252, 222) }),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(30.0)),
+        });
+        canvas.draw(
+            &line_text,
+            DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 120.0 + 34.0 * index as f32)),
+        );
+    }
+
+    let prompt = match &game.profile_edit {
+        ProfileEdit::Browsing => {
+            "Up/Down to choose, space to play as them, F2 to rename,\nX to delete, K for key bindings, escape to go back".to_string()
+        }
+        ProfileEdit::Naming { text, .. } => format!(
+            "Name: {}_\n(up to {} letters, return to save, escape to cancel)",
+            text,
+            profiles::MAX_NAME_LENGTH
+        ),
+        ProfileEdit::ConfirmingDelete => "Delete this profile and all its progress? Y to confirm".to_string(),
+    };
+    let prompt_text = Text::new(TextFragment {
+        text: match &game.profile_error {
+            Some(error) => format!("{}\n{}", error, prompt),
+            None => prompt,
+        },
+        color: Some(if game.profile_error.is_some() { Color::RED } else { Color::GREEN }),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(24.0)),
+    });
+    canvas.draw(
+        &prompt_text,
+        DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, game.window_size.height as f32 - 120.0)),
+    );
+}
+
+fn draw_key_bindings_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let title_text = Text::new(TextFragment {
+        text: format!("KEYS FOR {}", game.profiles.current.to_uppercase()),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(50.0)),
+    });
+    canvas.draw(&title_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 40.0)));
+
+    for (index, action) in settings::ACTIONS.iter().enumerate() {
+        let selected = index == game.binding_cursor;
+        let key = if selected && game.awaiting_binding {
+            "press a key...".to_string()
+        } else {
+            key_name(game.settings.keys.key_for(*action))
+        };
+        let line_text = Text::new(TextFragment {
+            text: format!("{} {:<20} {}", if selected { ">" } else { " " }, action.name(), key),
+            color: Some(if selected { Color::YELLOW } else { Color::from_rgb(71, 252, 222) }),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(30.0)),
+        });
+        canvas.draw(
+            &line_text,
+            DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 120.0 + 34.0 * index as f32)),
+        );
+    }
+
+    let help_text = Text::new(TextFragment {
+        text: "Up/Down to choose, return to change, escape to go back".to_string(),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(24.0)),
+    });
+    canvas.draw(
+        &help_text,
+        DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, game.window_size.height as f32 - 60.0)),
+    );
+}
+
+fn draw_stats_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let title_text = Text::new(TextFragment {
+        text: format!("STATS FOR {}", game.profiles.current.to_uppercase()),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(50.0)),
+    });
+    canvas.draw(&title_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 40.0)));
+
+    let stats = &game.stats;
+    let average_left = match stats.average_seconds_left() {
+        Some(seconds) => format!("{:.1}s", seconds),
+        None => "-".to_string(),
+    };
+    let favourites = stats
+        .favourite_numbers(3)
+        .iter()
+        .map(|(number, times)| format!("{} ({}x)", number, times))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let lines = [
+        ("Games played", stats.games_played.to_string()),
+        ("Rounds won", stats.rounds_won.to_string()),
+        ("Overshoots", stats.overshoots.to_string()),
+        ("Time-outs", stats.time_outs.to_string()),
+        ("Mines hit", stats.mines_hit.to_string()),
+        ("Average time left on wins", average_left),
+        ("Largest target hit", stats.largest_target_hit.to_string()),
+        ("Best winning streak", stats.best_streak.to_string()),
+        ("Favourite numbers", if favourites.is_empty() { "-".to_string() } else { favourites }),
+    ];
+    for (index, (name, value)) in lines.iter().enumerate() {
+        let line_text = Text::new(TextFragment {
+            text: format!("{:<27} {}", name, value),
+            color: Some(Color::from_rgb(71, 252, 222)),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(30.0)),
+        });
+        canvas.draw(
+            &line_text,
+            DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 120.0 + 34.0 * index as f32)),
+        );
+    }
+
+    let help_text = Text::new(TextFragment {
+        text: "Press space or escape to go back".to_string(),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(24.0)),
+    });
+    canvas.draw(
+        &help_text,
+        DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, game.window_size.height as f32 - 60.0)),
+    );
+}
+
+fn draw_achievements_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let title_text = Text::new(TextFragment {
+        text: format!(
+            "ACHIEVEMENTS  {}/{}",
+            game.achievements.unlocked.len(),
+            achievements::ACHIEVEMENTS.len()
+        ),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(50.0)),
+    });
+    canvas.draw(&title_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 40.0)));
+
+    for (index, achievement) in achievements::ACHIEVEMENTS.iter().enumerate() {
+        let unlocked_at = game.achievements.unlocked.get(achievement.id);
+        let status = match unlocked_at {
+            Some(seconds) => daily::date_string(seconds / 86_400),
+            None => "locked".to_string(),
+        };
+        let line_text = Text::new(TextFragment {
+            text: format!("{:<14} {:<44} {}", achievement.name, achievement.description, status),
+            color: Some(if unlocked_at.is_some() { Color::YELLOW } else { Color::from_rgb(120, 120, 120) }),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(20.0)),
+        });
+        canvas.draw(
+            &line_text,
+            DrawParam::from(Vec2::new(game.window_size.width as f32 / 12.0, 120.0 + 28.0 * index as f32)),
+        );
+    }
+
+    let help_text = Text::new(TextFragment {
+        text: "Press space or escape to go back".to_string(),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(24.0)),
+    });
+    canvas.draw(
+        &help_text,
+        DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, game.window_size.height as f32 - 60.0)),
+    );
+}
+
+fn draw_level_select_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let max_stars = game.campaign_levels.len() as u32 * campaign::MAX_STARS as u32;
+    let title_text = Text::new(TextFragment {
+        text: format!("CAMPAIGN  {}/{} stars", game.campaign_progress.total_stars(), max_stars),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(50.0)),
+    });
+    canvas.draw(&title_text, DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 40.0)));
+
+    // One line per world heading and per level, scrolled so the selected level stays visible:
+    let mut lines: Vec<(String, Color)> = vec![];
+    let mut cursor_line = 0;
+    let mut current_world = "";
+    for (index, level) in game.campaign_levels.iter().enumerate() {
+        if level.world != current_world {
+            current_world = &level.world;
+            lines.push((current_world.to_uppercase(), Color::WHITE));
+        }
+        let unlocked = game.campaign_progress.is_unlocked(&game.campaign_levels, index);
+        let selected = index == game.level_select_cursor;
+        if selected {
+            cursor_line = lines.len();
+        }
+        let status = if unlocked {
+            star_string(game.campaign_progress.stars_for(level))
+        } else {
+            "locked".to_string()
+        };
+        let color = match (selected, unlocked) {
+            (true, _) => Color::YELLOW,
+            (false, true) => Color::from_rgb(71, 252, 222),
+            (false, false) => Color::new(0.5, 0.5, 0.5, 1.0),
+        };
+        lines.push((format!("{} {:<30} {}", if selected { ">" } else { " " }, level.name, status), color));
+    }
+
+    let visible_lines: usize = 16;
+    let first_line = (cursor_line + 1).saturating_sub(visible_lines);
+    for (row, (line, color)) in lines.iter().skip(first_line).take(visible_lines).enumerate() {
+        let line_text = Text::new(TextFragment {
+            text: line.clone(),
+            color: Some(*color),
+            font: Some("LiberationMono-Regular".into()),
+            scale: Some(PxScale::from(30.0)),
+        });
+        canvas.draw(
+            &line_text,
+            DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, 120.0 + 34.0 * row as f32)),
+        );
+    }
+
+    let help_text = Text::new(TextFragment {
+        text: "Up/Down to choose, space to play, escape to go back".to_string(),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(24.0)),
+    });
+    canvas.draw(
+        &help_text,
+        DrawParam::from(Vec2::new(game.window_size.width as f32 / 9.0, game.window_size.height as f32 - 60.0)),
+    );
+}
+
+fn draw_daily_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let record = &game.daily_record;
+    let todays_score = match record.score_on(game.daily_day) {
+        Some(score) => format!("Today's score: {}", score),
+        None => "Not played today yet".to_string(),
+    };
+    let best_score = match record.best_score() {
+        Some(score) => format!("Best daily score: {}", score),
+        None => "Best daily score: -".to_string(),
+    };
+    let play_prompt = if record.has_played(game.daily_day) {
+        "Press space for a practice run (not scored)"
+    } else {
+        "Press space to play (one scored attempt per day)"
+    };
+
+    let title_text = Text::new(TextFragment {
+        text: format!("DAILY CHALLENGE\n{}", daily::date_string(game.daily_day)),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(65.0)),
+    });
+
+    let details_text = Text::new(TextFragment {
+        text: format!(
+            "{}\n{}\nStreak: {} days (best {})\n\n{}\nEscape to go back",
+            todays_score,
+            best_score,
+            record.current_streak(game.daily_day),
+            record.best_streak,
+            play_prompt,
+        ),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(30.0)),
+    });
+
+    canvas.draw(
+        &title_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 9.0,
+            game.window_size.height as f32 / 6.0,
+        )),
+    );
+
+    canvas.draw(
+        &details_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 9.0,
+            game.window_size.height as f32 / 6.0 + 180.0,
+        )),
+    );
+}
+
+fn draw_zen_setup_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let (min_target, max_target) = rules::ZEN_TARGET_RANGES[game.settings.zen_target_range];
+    let (speed_name, _, _) = rules::ZEN_SPEEDS[game.settings.zen_speed];
+
+    let title_text = Text::new(TextFragment {
+        text: "ZEN PRACTICE".to_string(),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(65.0)),
+    });
+
+    let settings_text = Text::new(TextFragment {
+        text: format!(
+            "No timer, no lives. Overshoots are explained, not punished.\n\nTargets (up/down): {} to {}\nBubble speed (left/right): {}\n\nPress space to start, escape to go back.\nEscape during play returns here.",
+            min_target, max_target, speed_name
+        ),
+        color: Some(Color::GREEN),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(30.0)),
+    });
+
+    canvas.draw(
+        &title_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 9.0,
+            game.window_size.height as f32 / 6.0,
+        )),
+    );
+
+    canvas.draw(
+        &settings_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 9.0,
+            game.window_size.height as f32 / 6.0 + 120.0,
+        )),
+    );
+}
+
+fn draw_time_up_screen(_ctx: &Context, canvas: &mut Canvas, game: &GameState) {
+    let time_up_text = Text::new(TextFragment {
+        text: format!("TIME'S UP!\nTargets hit: {}", game.targets_hit),
+        color: Some(Color::YELLOW),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(100.0)),
+    });
+
+    let best_text = Text::new(TextFragment {
+        text: if game.new_best_score {
+            "New best!".to_string()
+        } else {
+            format!("Your best: {}", game.high_scores.best_for(session::mode_name(game.play_mode)).unwrap_or(game.targets_hit))
+        },
+        color: Some(Color::YELLOW),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(40.0)),
+    });
+
+    let press_space_text = Text::new(TextFragment {
+        text: "Press space to continue...".to_string(),
+        color: Some(Color::YELLOW),
+        font: Some("LiberationMono-Regular".into()),
+        scale: Some(PxScale::from(50.0)),
+    });
+
+    canvas.draw(
+        &time_up_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0,
+        )),
+    );
+
+    canvas.draw(
+        &best_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0 + 200.0,
+        )),
+    );
+
+    canvas.draw(
+        &press_space_text,
+        DrawParam::from(Vec2::new(
+            game.window_size.width as f32 / 7.0,
+            game.window_size.height as f32 / 3.0 + 300.0,
+        )),
+    );
+}
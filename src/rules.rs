@@ -0,0 +1,121 @@
+use std::rc::Rc;
+
+use crate::constants;
+use crate::policies::{
+    CampaignLevel, CountdownTargets, CurriculumTargets, EndlessTargets, LifePolicy, LimitedLives, NoLives,
+    RoundProgressionPolicy, ScoringPolicy, ShrinkingClock, SteadyClock, StreakScoring, TargetGenerator, ZenTargets,
+};
+
+/// What happens when the caught numbers go past the target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OvershootRule {
+    /// Lose a life and end the round.
+    LoseLife,
+    /// Knock the last catch back off the total and tell the player what happened.
+    Feedback,
+    /// Knock the last catch back off the total and take seconds off the clock.
+    LoseSeconds(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Clock {
+    /// No time limit at all.
+    Off,
+    /// Each round gets its own time limit, running out costs a life.
+    PerRound,
+    /// One clock for the whole game, the game ends when it runs out.
+    Global { seconds: u64 },
+}
+
+/// The rules a game is played under, kept separate from the game loop so each
+/// PlayMode can be put together from these pieces without special cases in `update`.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub clock: Clock,
+    pub overshoot: OvershootRule,
+    pub scoring: Rc<dyn ScoringPolicy>,
+    pub lives: Rc<dyn LifePolicy>,
+    pub progression: Rc<dyn RoundProgressionPolicy>,
+    pub targets: Rc<dyn TargetGenerator>,
+}
+
+impl Rules {
+    /// Endless play, and the daily challenge.
+    pub fn standard() -> Self {
+        Self {
+            clock: Clock::PerRound,
+            overshoot: OvershootRule::LoseLife,
+            scoring: Rc::new(StreakScoring { time_bonus: true }),
+            lives: Rc::new(LimitedLives {
+                lives: constants::STARTING_LIVES,
+                max: constants::MAX_LIVES,
+                points_per_extra_life: Some(constants::EXTRA_LIFE_EVERY_POINTS),
+            }),
+            progression: Rc::new(ShrinkingClock),
+            targets: Rc::new(EndlessTargets),
+        }
+    }
+
+    pub fn countdown() -> Self {
+        Self {
+            targets: Rc::new(CountdownTargets),
+            ..Self::standard()
+        }
+    }
+
+    /// A single campaign level, with the time it sets.
+    pub fn campaign(level_index: usize) -> Self {
+        Self {
+            progression: Rc::new(SteadyClock { breaks_between_rounds: true }),
+            targets: Rc::new(CampaignLevel { index: level_index }),
+            ..Self::standard()
+        }
+    }
+
+    /// Practice without pressure: no clock, no lives, overshoots are explained rather than punished.
+    pub fn zen() -> Self {
+        Self {
+            clock: Clock::Off,
+            overshoot: OvershootRule::Feedback,
+            scoring: Rc::new(StreakScoring { time_bonus: false }),
+            lives: Rc::new(NoLives),
+            progression: Rc::new(SteadyClock { breaks_between_rounds: true }),
+            targets: Rc::new(ZenTargets),
+        }
+    }
+
+    /// Zen practice with targets from the maths curriculum.
+    pub fn curriculum() -> Self {
+        Self {
+            targets: Rc::new(CurriculumTargets),
+            ..Self::zen()
+        }
+    }
+
+    /// As many targets as possible against a single clock, and overshooting costs time
+    /// instead of lives.
+    pub fn time_attack() -> Self {
+        Self {
+            clock: Clock::Global { seconds: TIME_ATTACK_SECONDS },
+            overshoot: OvershootRule::LoseSeconds(TIME_ATTACK_OVERSHOOT_PENALTY_SECONDS),
+            scoring: Rc::new(StreakScoring { time_bonus: false }),
+            lives: Rc::new(NoLives),
+            progression: Rc::new(SteadyClock { breaks_between_rounds: false }),
+            targets: Rc::new(EndlessTargets),
+        }
+    }
+}
+
+pub const TIME_ATTACK_SECONDS: u64 = 180;
+pub const TIME_ATTACK_OVERSHOOT_PENALTY_SECONDS: u64 = 5;
+
+/// Target ranges the player can choose from in zen mode.
+pub const ZEN_TARGET_RANGES: &[(u32, u32)] = &[(5, 20), (10, 40), (20, 75), (50, 150)];
+
+/// Bubble speeds the player can choose from in zen mode: (name, min speed, max speed).
+pub const ZEN_SPEEDS: &[(&str, f32, f32)] = &[
+    ("Very slow", 0.5, 1.0),
+    ("Slow", 1.0, 1.8),
+    ("Normal", 1.5, 2.9),
+    ("Fast", 2.5, 4.0),
+];
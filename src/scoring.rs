@@ -0,0 +1,63 @@
+use crate::constants;
+
+/// How the points for a won round were worked out, shown on the win screen.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreBreakdown {
+    pub base: u32,
+    pub time_bonus: u32,
+    pub bubbles: usize,
+    pub few_bubbles_bonus: u32,
+    /// Clean wins in a row, including this one.
+    pub streak: u32,
+    pub multiplier: u32,
+    pub overshoots: u32,
+    pub overshoot_penalty: u32,
+}
+
+/// 1 for the first few clean wins in a row, then one more for every STREAK_WINS_PER_MULTIPLIER.
+pub fn streak_multiplier(streak: u32) -> u32 {
+    (1 + streak / constants::STREAK_WINS_PER_MULTIPLIER).min(constants::MAX_STREAK_MULTIPLIER)
+}
+
+impl ScoreBreakdown {
+    /// `overshoots` are the ones made during the round without it ending, e.g. in zen practice.
+    pub fn for_win(time_bonus: u32, bubbles: usize, streak: u32, overshoots: u32) -> Self {
+        Self {
+            base: 1,
+            time_bonus,
+            bubbles,
+            few_bubbles_bonus: constants::FEW_BUBBLES_BONUS_BELOW.saturating_sub(bubbles) as u32,
+            streak,
+            multiplier: streak_multiplier(streak),
+            overshoots,
+            overshoot_penalty: overshoots * constants::OVERSHOOT_PENALTY_POINTS,
+        }
+    }
+
+    /// The bonuses are multiplied by the streak before overshoots are taken off.
+    pub fn total(&self) -> u32 {
+        ((self.base + self.time_bonus + self.few_bubbles_bonus) * self.multiplier).saturating_sub(self.overshoot_penalty)
+    }
+
+    /// One line per part of the score that counted this round, ending with the total.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{:<28}{:>5}", "Round won", format!("+{}", self.base))];
+        if self.time_bonus > 0 {
+            lines.push(format!("{:<28}{:>5}", "Time bonus", format!("+{}", self.time_bonus)));
+        }
+        if self.few_bubbles_bonus > 0 {
+            let label = format!("Only {} bubble{}", self.bubbles, if self.bubbles == 1 { "" } else { "s" });
+            lines.push(format!("{:<28}{:>5}", label, format!("+{}", self.few_bubbles_bonus)));
+        }
+        if self.multiplier > 1 {
+            let label = format!("{} clean wins in a row", self.streak);
+            lines.push(format!("{:<28}{:>5}", label, format!("x{}", self.multiplier)));
+        }
+        if self.overshoot_penalty > 0 {
+            let label = format!("Overshot {} time{}", self.overshoots, if self.overshoots == 1 { "" } else { "s" });
+            lines.push(format!("{:<28}{:>5}", label, format!("-{}", self.overshoot_penalty)));
+        }
+        lines.push(format!("{:<28}{:>5}", "Total", format!("+{}", self.total())));
+        lines
+    }
+}
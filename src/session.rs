@@ -0,0 +1,221 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::daily;
+use crate::structs::PlayMode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RoundOutcome {
+    Cleared,
+    Overshot,
+    TimedOut,
+    /// A mine took the last life.
+    HitMine,
+    /// Left a zen or maths practice round with escape.
+    Abandoned,
+}
+
+/// What happened in one round, for the teacher/parent report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundLog {
+    pub round: u32,
+    pub target: u32,
+    pub objective: String,
+    pub caught: Vec<i32>,
+    /// Overshoots during the round. Modes that don't end the round on an overshoot can have several.
+    pub overshoots: u32,
+    pub outcome: RoundOutcome,
+    pub seconds: u64,
+}
+
+/// One game, from picking a mode on the intro screen to going back to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Seconds since 1970-01-01 (UTC).
+    pub started_at: u64,
+    pub mode: String,
+    pub rounds: Vec<RoundLog>,
+}
+
+/// Every session played, saved between games.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionLog {
+    pub sessions: Vec<Session>,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn mode_name(mode: PlayMode) -> &'static str {
+    match mode {
+        PlayMode::Endless => "Endless",
+        PlayMode::Campaign { .. } => "Campaign",
+        PlayMode::Daily { .. } => "Daily challenge",
+        PlayMode::Zen => "Zen practice",
+        PlayMode::TimeAttack => "Time attack",
+        PlayMode::Countdown => "Countdown",
+        PlayMode::Curriculum => "Maths practice",
+    }
+}
+
+/// e.g. "2024-03-09 14:05" (UTC).
+fn timestamp(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    format!("{} {:02}:{:02}", daily::date_string(seconds / 86_400), minutes / 60 % 24, minutes % 60)
+}
+
+fn outcome_name(outcome: RoundOutcome) -> &'static str {
+    match outcome {
+        RoundOutcome::Cleared => "Cleared",
+        RoundOutcome::Overshot => "Overshot",
+        RoundOutcome::TimedOut => "Timed out",
+        RoundOutcome::HitMine => "Hit a mine",
+        RoundOutcome::Abandoned => "Abandoned",
+    }
+}
+
+fn caught_text(caught: &[i32]) -> String {
+    caught.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+impl Session {
+    fn count(&self, outcome: RoundOutcome) -> usize {
+        self.rounds.iter().filter(|r| r.outcome == outcome).count()
+    }
+
+    fn average_seconds(&self) -> u64 {
+        self.rounds.iter().map(|r| r.seconds).sum::<u64>() / (self.rounds.len().max(1) as u64)
+    }
+}
+
+impl SessionLog {
+    /// Adds a round to the session that started at `started_at`, opening the session if it's new.
+    pub fn record(&mut self, started_at: u64, mode: PlayMode, round: RoundLog) {
+        if self.sessions.last().is_none_or(|s| s.started_at != started_at) {
+            self.sessions.push(Session {
+                started_at,
+                mode: mode_name(mode).to_string(),
+                rounds: vec![],
+            });
+        }
+        if let Some(session) = self.sessions.last_mut() {
+            session.rounds.push(round);
+        }
+    }
+
+    /// One row per round.
+    pub fn csv_report(&self, player: &str) -> String {
+        let mut csv = "player,session_started,mode,round,target,objective,caught,overshoots,outcome,seconds\n".to_string();
+        for session in &self.sessions {
+            for round in &session.rounds {
+                csv += &format!(
+                    "{},{},{},{},{},{},{},{},{},{}\n",
+                    csv_field(player),
+                    timestamp(session.started_at),
+                    csv_field(&session.mode),
+                    round.round,
+                    round.target,
+                    csv_field(&round.objective),
+                    caught_text(&round.caught),
+                    round.overshoots,
+                    outcome_name(round.outcome),
+                    round.seconds
+                );
+            }
+        }
+        csv
+    }
+
+    /// A single HTML page with a summary line per session and each session's rounds, newest first.
+    pub fn html_report(&self, player: &str) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Rust Bubbles report: {player}</title>\n\
+             <style>\nbody {{ font-family: sans-serif; margin: 2em; }}\ntable {{ border-collapse: collapse; margin-bottom: 2em; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\nth {{ background: #eee; }}\n\
+             .Cleared {{ color: green; }} .Overshot, .Timed, .Hit {{ color: #c00; }}\n</style>\n</head>\n<body>\n\
+             <h1>Rust Bubbles report: {player}</h1>\n",
+            player = escape(player)
+        );
+        if self.sessions.is_empty() {
+            html += "<p>No rounds played yet.</p>\n";
+        }
+        for session in self.sessions.iter().rev() {
+            html += &format!(
+                "<h2>{} &ndash; {}</h2>\n<p>{} rounds: {} cleared, {} overshot, {} timed out. Average {}s per round.</p>\n",
+                timestamp(session.started_at),
+                escape(&session.mode),
+                session.rounds.len(),
+                session.count(RoundOutcome::Cleared),
+                session.count(RoundOutcome::Overshot),
+                session.count(RoundOutcome::TimedOut),
+                session.average_seconds()
+            );
+            html += "<table>\n<tr><th>Round</th><th>Target</th><th>Objective</th><th>Caught</th><th>Overshoots</th><th>Outcome</th><th>Time</th></tr>\n";
+            for round in &session.rounds {
+                let outcome = outcome_name(round.outcome);
+                html += &format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}s</td></tr>\n",
+                    round.round,
+                    round.target,
+                    escape(&round.objective),
+                    caught_text(&round.caught),
+                    round.overshoots,
+                    outcome.split(' ').next().unwrap_or_default(),
+                    outcome,
+                    round.seconds
+                );
+            }
+            html += "</table>\n";
+        }
+        html + "</body>\n</html>\n"
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Quotes a CSV field when it needs it, doubling any quotes inside (RFC 4180).
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_and_escaped() {
+        let log = SessionLog {
+            sessions: vec![Session {
+                started_at: 0,
+                mode: "Campaign".to_string(),
+                rounds: vec![RoundLog {
+                    round: 1,
+                    target: 12,
+                    objective: "Sum with \"no\" repeats, please".to_string(),
+                    caught: vec![5, 7],
+                    overshoots: 0,
+                    outcome: RoundOutcome::Cleared,
+                    seconds: 9,
+                }],
+            }],
+        };
+        let csv = log.csv_report("Sam");
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "Sam,1970-01-01 00:00,Campaign,1,12,\"Sum with \"\"no\"\" repeats, please\",5 7,0,Cleared,9"
+        );
+        assert_eq!(csv_field("plain"), "plain");
+    }
+}
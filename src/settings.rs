@@ -0,0 +1,176 @@
+use ggez::input::keyboard::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+
+/// Keys that can be bound to an action. Saved by name, e.g. "Left" or "W".
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Space, KeyCode::Tab, KeyCode::Back, KeyCode::LShift, KeyCode::RShift,
+    KeyCode::LControl, KeyCode::RControl, KeyCode::LAlt, KeyCode::RAlt,
+];
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+pub fn is_bindable(key: KeyCode) -> bool {
+    BINDABLE_KEYS.contains(&key)
+}
+
+/// Lets KeyCode fields be saved as their names, as winit's serde support isn't switched on.
+mod key_by_name {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(key: &KeyCode, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&key_name(*key))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<KeyCode, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        BINDABLE_KEYS
+            .iter()
+            .copied()
+            .find(|key| key_name(*key) == name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown key {}", name)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Left,
+    Right,
+    Up,
+    Down,
+    Pause,
+    Release,
+    Hint,
+}
+
+pub const ACTIONS: &[Action] = &[
+    Action::Left,
+    Action::Right,
+    Action::Up,
+    Action::Down,
+    Action::Pause,
+    Action::Release,
+    Action::Hint,
+];
+
+impl Action {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Left => "Move left",
+            Action::Right => "Move right",
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Pause => "Pause",
+            Action::Release => "Release last catch",
+            Action::Hint => "Hint",
+        }
+    }
+}
+
+/// The keys for everything done during a round. Menus always use the same keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    #[serde(with = "key_by_name")]
+    pub left: KeyCode,
+    #[serde(with = "key_by_name")]
+    pub right: KeyCode,
+    #[serde(with = "key_by_name")]
+    pub up: KeyCode,
+    #[serde(with = "key_by_name")]
+    pub down: KeyCode,
+    #[serde(with = "key_by_name")]
+    pub pause: KeyCode,
+    #[serde(with = "key_by_name")]
+    pub release: KeyCode,
+    #[serde(with = "key_by_name")]
+    pub hint: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            left: KeyCode::Left,
+            right: KeyCode::Right,
+            up: KeyCode::Up,
+            down: KeyCode::Down,
+            pause: KeyCode::P,
+            release: KeyCode::R,
+            hint: KeyCode::H,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn key_for(&self, action: Action) -> KeyCode {
+        match action {
+            Action::Left => self.left,
+            Action::Right => self.right,
+            Action::Up => self.up,
+            Action::Down => self.down,
+            Action::Pause => self.pause,
+            Action::Release => self.release,
+            Action::Hint => self.hint,
+        }
+    }
+
+    /// Binds the key to the action. Whatever action had the key before swaps over to the
+    /// action's old key, so no two actions ever share a key.
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        let old_key = self.key_for(action);
+        if let Some(other) = ACTIONS.iter().copied().find(|a| *a != action && self.key_for(*a) == key) {
+            *self.slot(other) = old_key;
+        }
+        *self.slot(action) = key;
+    }
+
+    fn slot(&mut self, action: Action) -> &mut KeyCode {
+        match action {
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+            Action::Pause => &mut self.pause,
+            Action::Release => &mut self.release,
+            Action::Hint => &mut self.hint,
+        }
+    }
+}
+
+/// A player's own choices, saved with their profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    /// Move the ship at full speed straight away and stop dead, instead of speeding up and drifting.
+    pub arcade_movement: bool,
+    /// Mix negative bubbles into endless, zen, countdown and time attack.
+    pub negative_bubbles: bool,
+    pub zen_target_range: usize,
+    pub zen_speed: usize,
+    pub keys: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::Normal,
+            arcade_movement: false,
+            negative_bubbles: false,
+            zen_target_range: 0,
+            zen_speed: 1,
+            keys: KeyBindings::default(),
+        }
+    }
+}
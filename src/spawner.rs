@@ -0,0 +1,328 @@
+use std::fmt::Debug;
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+use crate::constants;
+use crate::curriculum::{self, Stage};
+use crate::levels::{Level, SpawnWeight};
+use crate::objectives::Objective;
+use crate::planner::SpawnPlanner;
+use crate::structs::BubbleKind;
+
+/// How a level's generated bubbles pick their numbers. Levels with a `bubble_sequence`
+/// or `spawn_table` use those instead.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+pub enum SpawnStrategy {
+    /// Numbers that suit the objective, often sized to what's still needed, with negatives
+    /// and number facts due for review mixed in.
+    #[default]
+    Shortfall,
+    /// Any number up to the target, equally likely.
+    Uniform,
+    /// Shortfall numbers written as sums from the player's maths practice stage.
+    Curriculum,
+}
+
+/// What a spawner can see when picking the next number.
+pub struct SpawnContext<'a> {
+    pub level: &'a Level,
+    pub target: u32,
+    pub caught: &'a [i32],
+    pub rng: &'a mut ChaCha8Rng,
+    /// A number that would set up a number fact due for review, if there is one.
+    pub review: Option<i32>,
+}
+
+/// Picks the numbers on a round's number bubbles. Mines, decoys and hearts are rolled
+/// for before the spawner is asked, from the level's probabilities.
+pub trait Spawner: Debug {
+    fn next_number(&mut self, spawn: &mut SpawnContext) -> i32;
+
+    /// Whether a mine, decoy or heart can take the next place instead of a number bubble.
+    /// Spawners that keep rounds finishable can hold on to it for a number they need.
+    fn allows_other_bubble(&mut self, _spawn: &SpawnContext) -> bool {
+        true
+    }
+
+    /// What to write on a number bubble instead of the number.
+    fn label(&self, _number: i32, _rng: &mut ChaCha8Rng) -> Option<String> {
+        None
+    }
+}
+
+/// The spawner for a round on the level. `stage` is only used by the curriculum strategy.
+pub fn for_level(level: &Level, stage: &'static Stage) -> Box<dyn Spawner> {
+    if !level.bubble_sequence.is_empty() {
+        return Box::new(ScriptedWave { sequence: level.bubble_sequence.clone(), next: 0 });
+    }
+    if !level.spawn_table.is_empty() {
+        return Box::new(WeightedSpawner { table: level.spawn_table.clone() });
+    }
+    match level.spawner {
+        SpawnStrategy::Shortfall => Box::new(ShortfallSpawner::new(level.plan_window)),
+        SpawnStrategy::Uniform => Box::new(UniformSpawner { planner: SpawnPlanner::new(level.plan_window) }),
+        SpawnStrategy::Curriculum => Box::new(CurriculumSpawner {
+            stage,
+            numbers: ShortfallSpawner::new(level.plan_window),
+        }),
+    }
+}
+
+/// Rolls for what the next bubble is from the level's probabilities, and asks the spawner
+/// for its number when it's a number bubble. `hearts` is whether hearts can turn up now.
+/// When they can't, e.g. with lives at the maximum, the heart share of the roll goes to
+/// number bubbles, so a level spawns slightly more numbers than its probabilities say.
+pub fn next_bubble(spawner: &mut dyn Spawner, spawn: &mut SpawnContext, hearts: bool) -> (BubbleKind, i32) {
+    let level = spawn.level;
+    let roll = spawn.rng.gen::<f64>();
+    let kind = if roll < level.mine_probability {
+        Some(BubbleKind::Mine)
+    } else if roll < level.mine_probability + level.decoy_probability {
+        Some(BubbleKind::Decoy)
+    } else if hearts && roll < level.mine_probability + level.decoy_probability + level.heart_probability {
+        Some(BubbleKind::Heart)
+    } else {
+        None
+    };
+    match kind {
+        Some(kind) if spawner.allows_other_bubble(spawn) => (kind, 0),
+        _ => (BubbleKind::Number, spawner.next_number(spawn)),
+    }
+}
+
+pub fn random_between(rng: &mut impl Rng, lower: f32, upper: f32) -> f32 {
+    let window: f32 = upper - lower;
+    lower + ((rng.gen::<f64>()) * window as f64) as f32
+}
+
+fn shortfall(spawn: &SpawnContext) -> i32 {
+    spawn.target as i32 - spawn.caught.iter().sum::<i32>()
+}
+
+/// Runs a generated number past the spawn planner, for objectives that are only about
+/// adding up to the target. The others pick their own numbers to keep the round winnable.
+fn plan(planner: &mut SpawnPlanner, spawn: &SpawnContext, number: i32) -> i32 {
+    if !matches!(spawn.level.objective, Objective::Sum | Objective::Countdown) {
+        return number;
+    }
+    planner.plan(number, shortfall(spawn))
+}
+
+/// Lets the spawn planner keep the next place for a number, for the same objectives as `plan`.
+fn allow_other(planner: &mut SpawnPlanner, spawn: &SpawnContext) -> bool {
+    if !matches!(spawn.level.objective, Objective::Sum | Objective::Countdown) {
+        return true;
+    }
+    planner.allow_other(shortfall(spawn))
+}
+
+/// The level's numbers in order, starting again from the first once they run out.
+#[derive(Debug)]
+pub struct ScriptedWave {
+    sequence: Vec<i32>,
+    next: usize,
+}
+
+impl Spawner for ScriptedWave {
+    fn next_number(&mut self, _spawn: &mut SpawnContext) -> i32 {
+        let number = self.sequence[self.next % self.sequence.len()];
+        self.next += 1;
+        number
+    }
+}
+
+/// Numbers picked from the level's table, more often the higher their weight.
+#[derive(Debug)]
+pub struct WeightedSpawner {
+    table: Vec<SpawnWeight>,
+}
+
+impl Spawner for WeightedSpawner {
+    fn next_number(&mut self, spawn: &mut SpawnContext) -> i32 {
+        self.table.choose_weighted(spawn.rng, |s| s.weight).map(|s| s.number).unwrap_or(1)
+    }
+}
+
+#[derive(Debug)]
+pub struct UniformSpawner {
+    planner: SpawnPlanner,
+}
+
+impl Spawner for UniformSpawner {
+    fn next_number(&mut self, spawn: &mut SpawnContext) -> i32 {
+        let number = random_between(spawn.rng, 1.0, spawn.target as f32) as i32;
+        plan(&mut self.planner, spawn, number)
+    }
+
+    fn allows_other_bubble(&mut self, spawn: &SpawnContext) -> bool {
+        allow_other(&mut self.planner, spawn)
+    }
+}
+
+/// To balance making the game challenging but not too hard, some of the time the number
+/// is in the same range as the current shortfall, so you're not stuck needing a 2 and
+/// having to wait through random numbers between 1 and 100 for e.g.
+#[derive(Debug)]
+pub struct ShortfallSpawner {
+    planner: SpawnPlanner,
+}
+
+impl ShortfallSpawner {
+    pub fn new(plan_window: usize) -> Self {
+        Self { planner: SpawnPlanner::new(plan_window) }
+    }
+
+    /// Rolls for a negative bubble, when the level has them. They turn up more often once
+    /// the total is past the target, and are then often just the right size to get back.
+    fn negative_number(spawn: &mut SpawnContext) -> Option<i32> {
+        if spawn.level.negative_probability <= 0.0 || !spawn.level.objective.allows_negatives() {
+            return None;
+        }
+        let over = -shortfall(spawn);
+        let probability = if over > 0 {
+            constants::NEGATIVE_BUBBLE_WHEN_OVER_PROBABILITY
+        } else {
+            spawn.level.negative_probability
+        };
+        if !spawn.rng.gen_bool(probability) {
+            return None;
+        }
+        if over > 0 && spawn.rng.gen_bool(constants::RESPECT_SHORTFALL_PROBABILITY) {
+            return Some(-spawn.rng.gen_range(1..=over));
+        }
+        Some(-spawn.rng.gen_range(1..=constants::MAX_NEGATIVE_BUBBLE))
+    }
+
+    /// The number to offer before the planner has its say.
+    fn candidate(spawn: &mut SpawnContext) -> i32 {
+        if let Some(number) = Self::negative_number(spawn) {
+            return number;
+        }
+        if let Some(number) = spawn.review {
+            if spawn.rng.gen_bool(constants::FACT_REVIEW_PROBABILITY) {
+                return number;
+            }
+        }
+        if let Some(number) = spawn.level.objective.pick_number(spawn.caught, spawn.target, spawn.rng) {
+            return number;
+        }
+
+        let should_respect_shortfall_range: bool = spawn.rng.gen::<f64>() < constants::RESPECT_SHORTFALL_PROBABILITY;
+        let mut upper_limit: i32 = spawn.target as i32;
+        if should_respect_shortfall_range {
+            // With negative bubbles the total can be over the target, so keep this at least 1:
+            upper_limit = shortfall(spawn).max(1);
+        }
+        random_between(spawn.rng, 1.0, upper_limit as f32) as i32
+    }
+}
+
+impl Spawner for ShortfallSpawner {
+    fn next_number(&mut self, spawn: &mut SpawnContext) -> i32 {
+        let number = Self::candidate(spawn);
+        plan(&mut self.planner, spawn, number)
+    }
+
+    fn allows_other_bubble(&mut self, spawn: &SpawnContext) -> bool {
+        allow_other(&mut self.planner, spawn)
+    }
+}
+
+/// Numbers for the daily challenge, which has to be the same for everyone whatever they've
+/// caught: it picks as if nothing has been caught yet, and doesn't plan ahead.
+#[derive(Debug)]
+pub struct DailySpawner;
+
+impl Spawner for DailySpawner {
+    fn next_number(&mut self, spawn: &mut SpawnContext) -> i32 {
+        if let Some(number) = spawn.level.objective.pick_number(&[], spawn.target, spawn.rng) {
+            return number;
+        }
+        random_between(spawn.rng, 1.0, spawn.target as f32) as i32
+    }
+}
+
+/// Shortfall numbers, shown as sums from a maths practice stage, e.g. 12 as "3×4".
+#[derive(Debug)]
+pub struct CurriculumSpawner {
+    stage: &'static Stage,
+    numbers: ShortfallSpawner,
+}
+
+impl Spawner for CurriculumSpawner {
+    fn next_number(&mut self, spawn: &mut SpawnContext) -> i32 {
+        self.numbers.next_number(spawn)
+    }
+
+    fn allows_other_bubble(&mut self, spawn: &SpawnContext) -> bool {
+        self.numbers.allows_other_bubble(spawn)
+    }
+
+    fn label(&self, number: i32, rng: &mut ChaCha8Rng) -> Option<String> {
+        (number > 0).then(|| curriculum::expression_for(number, self.stage, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daily;
+    use crate::planner;
+
+    #[test]
+    fn every_window_of_spawned_bubbles_can_finish_the_round() {
+        for seed in 0..300 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let target = rng.gen_range(5..=60);
+            let mut level = Level::endless(constants::HAZARDS_FROM_ROUND, target, Objective::Sum, 60);
+            level.heart_probability = 0.1;
+            level.negative_probability = constants::NEGATIVE_BUBBLE_PROBABILITY;
+            let mut spawner = for_level(&level, &curriculum::STAGES[0]);
+            let mut caught: Vec<i32> = vec![];
+            // What's spawned since the last catch, with 0 for mines, decoys and hearts:
+            let mut since_catch: Vec<i32> = vec![];
+            while caught.iter().sum::<i32>() < target as i32 {
+                let shortfall = target as i32 - caught.iter().sum::<i32>();
+                let mut spawn = SpawnContext { level: &level, target, caught: &caught, rng: &mut rng, review: Some(3) };
+                let (kind, number) = next_bubble(spawner.as_mut(), &mut spawn, true);
+                since_catch.push(if kind == BubbleKind::Number { number } else { 0 });
+                assert!(
+                    since_catch.windows(level.plan_window).all(|w| planner::can_complete(w, shortfall)),
+                    "seed {}: {:?} can't make {}",
+                    seed,
+                    since_catch,
+                    shortfall
+                );
+                if kind == BubbleKind::Number && (1..=shortfall).contains(&number) && rng.gen_bool(0.3) {
+                    caught.push(number);
+                    since_catch.clear();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn daily_bubbles_are_the_same_whatever_is_caught() {
+        let mut level = Level::endless(constants::HAZARDS_FROM_ROUND, 40, Objective::Sum, 60);
+        level.heart_probability = 0.1;
+        // Spawns a round's worth of daily bubbles, catching every `catch_every`th number bubble:
+        let spawn_round = |catch_every: u32| {
+            let mut spawner = DailySpawner;
+            let mut caught: Vec<i32> = vec![];
+            let mut bubbles = vec![];
+            for index in 0..200 {
+                let mut rng = ChaCha8Rng::seed_from_u64(daily::bubble_seed(20_000, 3, index));
+                let mut spawn = SpawnContext { level: &level, target: 40, caught: &caught, rng: &mut rng, review: None };
+                let (kind, number) = next_bubble(&mut spawner, &mut spawn, true);
+                if kind == BubbleKind::Number && index.is_multiple_of(catch_every) {
+                    caught.push(number);
+                }
+                bubbles.push((kind, number));
+            }
+            bubbles
+        };
+        assert_eq!(spawn_round(2), spawn_round(3));
+    }
+}
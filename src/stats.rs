@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Lifetime totals for a profile, shown on the stats screen.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u32,
+    pub rounds_won: u32,
+    pub overshoots: u32,
+    pub time_outs: u32,
+    pub mines_hit: u32,
+    /// Rounds won against a clock, and the seconds left on it summed over them.
+    pub timed_wins: u32,
+    pub seconds_left_on_wins: u64,
+    pub largest_target_hit: u32,
+    /// Rounds won in a row without an overshoot or time-out in between.
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// How often each number has been caught, keyed by the number.
+    pub numbers_caught: BTreeMap<String, u32>,
+}
+
+impl LifetimeStats {
+    pub fn game_started(&mut self) {
+        self.games_played += 1;
+    }
+
+    pub fn caught(&mut self, number: i32) {
+        *self.numbers_caught.entry(number.to_string()).or_insert(0) += 1;
+    }
+
+    /// `seconds_left` is None when the round had no clock.
+    pub fn round_won(&mut self, target: u32, seconds_left: Option<u64>) {
+        self.rounds_won += 1;
+        if let Some(seconds) = seconds_left {
+            self.timed_wins += 1;
+            self.seconds_left_on_wins += seconds;
+        }
+        self.largest_target_hit = self.largest_target_hit.max(target);
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+    }
+
+    pub fn overshot(&mut self) {
+        self.overshoots += 1;
+        self.current_streak = 0;
+    }
+
+    /// `lost_round` when the mine took the last life.
+    pub fn hit_mine(&mut self, lost_round: bool) {
+        self.mines_hit += 1;
+        if lost_round {
+            self.current_streak = 0;
+        }
+    }
+
+    pub fn timed_out(&mut self) {
+        self.time_outs += 1;
+        self.current_streak = 0;
+    }
+
+    pub fn average_seconds_left(&self) -> Option<f32> {
+        (self.timed_wins > 0).then(|| self.seconds_left_on_wins as f32 / self.timed_wins as f32)
+    }
+
+    /// The most caught numbers, most caught first, as (number, times caught).
+    pub fn favourite_numbers(&self, count: usize) -> Vec<(String, u32)> {
+        let mut numbers: Vec<(String, u32)> = self.numbers_caught.iter().map(|(n, c)| (n.clone(), *c)).collect();
+        numbers.sort_by_key(|(_, times)| std::cmp::Reverse(*times));
+        numbers.truncate(count);
+        numbers
+    }
+}
@@ -1,8 +1,35 @@
+use std::collections::VecDeque;
+
 use ggez::{glam::Vec2, winit::dpi::PhysicalSize};
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+
+use crate::achievements::Achievements;
+use crate::campaign::CampaignProgress;
+use crate::curriculum::CurriculumProgress;
+use crate::daily::DailyRecord;
+use crate::facts::FactBook;
+use crate::levels::Level;
+use crate::movement::MovementPattern;
+use crate::profiles::{HighScores, ProfileIndex};
+use crate::rules::Rules;
+use crate::scoring::ScoreBreakdown;
+use crate::session::SessionLog;
+use crate::settings::Settings;
+use crate::spawner::Spawner;
+use crate::stats::LifetimeStats;
 
 #[derive(Debug)]
 pub enum GameMode {
     IntroScreen,
+    StatsScreen,
+    AchievementsScreen,
+    ProfilesScreen,
+    KeyBindingsScreen,
+    LevelSelectScreen,
+    DailyScreen,
+    ZenSetupScreen,
+    TimeUpScreen,
     Running,
     NextRoundScreen,
     OvershotScreen,
@@ -11,35 +38,158 @@ pub enum GameMode {
     DeathScreen,
 }
 
+/// Which kind of game is being played, as opposed to GameMode which is the screen being shown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayMode {
+    Endless,
+    Campaign { level_index: usize },
+    /// Only the first game of the day is scored, any later ones are practice.
+    Daily { scored: bool },
+    Zen,
+    TimeAttack,
+    /// Subtract catches from the target down to zero, with smaller targets for younger players.
+    Countdown,
+    /// Bubbles show sums to work out, moving through the curriculum as each stage is mastered.
+    Curriculum,
+}
+
+/// What the profiles screen is doing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileEdit {
+    Browsing,
+    /// Typing a name for a new profile, or a new name for an existing one.
+    Naming { renaming: Option<String>, text: String },
+    ConfirmingDelete,
+}
+
 #[derive(Debug)]
 pub struct GameState {
     pub window_size: PhysicalSize<u32>,
     pub current_mode: GameMode,
+    pub play_mode: PlayMode,
+    pub rules: Rules,
+    pub rng: ChaCha8Rng,
     pub current_round: u32,
+    pub profiles: ProfileIndex,
+    pub settings: Settings,
+    pub high_scores: HighScores,
+    /// Whether the game just over beat the mode's best score, rather than tied it.
+    pub new_best_score: bool,
+    pub stats: LifetimeStats,
+    pub achievements: Achievements,
+    /// Names of achievements just unlocked, shown one at a time over whatever's on screen.
+    pub achievement_toasts: VecDeque<String>,
+    /// When the toast at the front of the queue stops showing, None until it's first shown.
+    pub toast_until_seconds: Option<u64>,
+    pub profile_cursor: usize,
+    pub profile_edit: ProfileEdit,
+    /// Shown on the profiles screen when a name can't be used, or a profile can't be changed.
+    pub profile_error: Option<String>,
+    pub binding_cursor: usize,
+    /// Waiting for the key to bind to the action under the cursor.
+    pub awaiting_binding: bool,
+    pub level: Level,
+    pub campaign_levels: Vec<Level>,
+    pub campaign_progress: CampaignProgress,
+    pub level_select_cursor: usize,
+    pub last_round_stars: u8,
+    pub daily_record: DailyRecord,
+    pub daily_day: u64,
+    pub curriculum_progress: CurriculumProgress,
+    /// Overshoots this round, a clean round counts towards mastering a curriculum stage.
+    pub mistakes_this_round: u32,
+    pub fact_book: FactBook,
+    /// Shown on the intro screen after exporting the progress report.
+    pub intro_message: Option<String>,
+    pub session_log: SessionLog,
+    /// When the current game was started, identifies its session in the log.
+    pub session_started_at: u64,
+    /// Game time the current round started at, for timing rounds in the session log.
+    pub round_log_start_seconds: u64,
+    pub feedback_message: Option<String>,
+    pub overshoot_reason: String,
+    pub feedback_until_seconds: u64,
+    /// Picks the numbers for the current round's bubbles.
+    pub spawner: Box<dyn Spawner>,
     pub round_start_time_seconds: u64,
     pub round_allowed_time_seconds: u64,
     pub round_time_remaining_seconds: u64,
+    pub round_time_penalty_seconds: u64,
     pub lives_remaining: u8,
     pub current_target: u32,
-    pub numbers_caught: Vec<u32>,
+    /// The last few targets this game, so they aren't asked for again straight away.
+    pub recent_targets: VecDeque<u32>,
+    pub numbers_caught: Vec<i32>,
+    /// What kind of bubble each of `numbers_caught` came from, so a release puts it back as it was.
+    pub caught_kinds: Vec<BubbleKind>,
+    pub last_release_time_seconds: Option<u64>,
+    /// When an overshoot that can still be undone by a release will end the round.
+    pub overshoot_grace_until_seconds: Option<u64>,
+    pub hints_used: u32,
+    pub hint_until_seconds: Option<u64>,
     pub score: u32,
-    pub round_time_bonus: u32,
+    /// Rounds won this game, whatever they scored.
+    pub targets_hit: u32,
+    /// How the last won round's points were worked out.
+    pub score_breakdown: ScoreBreakdown,
+    /// Clean wins in a row this game, for the score multiplier.
+    pub win_streak: u32,
+    /// The score that earns the next extra life.
+    pub next_extra_life_score: u32,
+    /// An extra life was earned this round, from points or a heart, for the win screen.
+    pub extra_life_earned: bool,
     pub ship: Ship,
     pub bubbles: Vec<Bubble>,
+    pub walls: Vec<Wall>,
     pub next_bubble_index: u32,
+    /// Bubbles spawned this round, not counting released ones, for seeding daily bubbles.
+    pub round_spawns: u32,
     pub paused: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BubbleKind {
+    Number,
+    /// Touching a mine costs some of the round's time, or a life in later rounds.
+    Mine,
+    /// Looks like a number bubble but is only ever worth zero.
+    Decoy,
+    /// Worth an extra life, up to the maximum.
+    Heart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum MinePenalty {
+    Life,
+    Seconds(u64),
+}
+
 #[derive(Debug)]
 pub struct Bubble {
     pub index: u32,
-    pub number: u32,
+    pub kind: BubbleKind,
+    pub number: i32,
+    /// Shown instead of the number when set, e.g. "3×4" in curriculum mode.
+    pub label: Option<String>,
     pub position: Vec2,
     pub speed: Vec2,
+    pub pattern: MovementPattern,
+    pub age_frames: u32,
+    pub anchor_x: f32,
 }
 
 #[derive(Debug)]
 pub struct Ship {
     pub position: Vec2,
+    /// Pixels per frame on each axis with arcade movement.
     pub speed: Vec2,
+    /// Pixels per frame the ship is drifting at, without arcade movement.
+    pub velocity: Vec2,
+}
+
+/// A barrier the ship can't pass through. Bubbles drift over it unaffected.
+#[derive(Debug)]
+pub struct Wall {
+    pub position: Vec2,
+    pub size: Vec2,
 }